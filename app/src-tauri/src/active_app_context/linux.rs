@@ -0,0 +1,279 @@
+use atspi::proxy::accessible::AccessibleProxy;
+use atspi::{AccessibilityConnection, Role, State};
+
+use super::shared::{
+    determine_focus_confidence_level, infer_browser_tab_title_from_window_title,
+    normalize_browser_document_origin, normalize_non_empty_focus_text,
+};
+use crate::active_app_context::{
+    ActiveAppContextSnapshot, FocusConfidenceLevel, FocusEventSource, FocusedApplication,
+    FocusedBrowserTab, FocusedTextElement, FocusedWindow, SupportedBrowser,
+};
+
+/// How many levels deep we're willing to walk the AT-SPI accessible tree
+/// looking for the focused element before giving up - deep enough for any
+/// real application window, shallow enough to bound a single snapshot call.
+const MAX_FOCUS_SEARCH_DEPTH: u8 = 40;
+
+#[derive(Debug, Clone)]
+struct FocusedAccessibleDetails {
+    application_name: Option<String>,
+    window_title: Option<String>,
+    role: Role,
+    name: Option<String>,
+    editable_text: Option<String>,
+    selected_text: Option<String>,
+    selection_range: Option<(usize, usize)>,
+    character_count: Option<usize>,
+}
+
+fn supported_browser_from_application_name(application_name: &str) -> Option<SupportedBrowser> {
+    match application_name.to_lowercase().as_str() {
+        "google-chrome" | "chrome" | "google-chrome-stable" => Some(SupportedBrowser::GoogleChrome),
+        "firefox" | "firefox-esr" => Some(SupportedBrowser::Firefox),
+        "microsoft-edge" | "microsoft-edge-stable" => Some(SupportedBrowser::MicrosoftEdge),
+        "brave-browser" | "brave" => Some(SupportedBrowser::BraveBrowser),
+        "opera" => Some(SupportedBrowser::Opera),
+        "vivaldi-stable" | "vivaldi" => Some(SupportedBrowser::Vivaldi),
+        "chromium" | "chromium-browser" => Some(SupportedBrowser::Chromium),
+        _ => None,
+    }
+}
+
+fn accessible_role_is_editable_text(role: Role) -> bool {
+    matches!(role, Role::Entry | Role::PasswordText | Role::Terminal)
+}
+
+fn accessible_role_is_secure_text(role: Role) -> bool {
+    matches!(role, Role::PasswordText)
+}
+
+async fn find_focused_descendant(
+    accessible: &AccessibleProxy<'_>,
+    remaining_depth: u8,
+) -> Option<AccessibleProxy<'static>> {
+    if remaining_depth == 0 {
+        return None;
+    }
+
+    let state_set = accessible.get_state().await.ok()?;
+    if state_set.contains(State::Focused) {
+        return accessible.to_owned().await.ok();
+    }
+
+    let child_count = accessible.child_count().await.ok()?;
+    for child_index in 0..child_count {
+        let child_accessible = accessible.get_child_at_index(child_index).await.ok()?;
+        if let Some(focused_descendant) = Box::pin(find_focused_descendant(
+            &child_accessible,
+            remaining_depth - 1,
+        ))
+        .await
+        {
+            return Some(focused_descendant);
+        }
+    }
+
+    None
+}
+
+async fn collect_focused_accessible_details(
+    connection: &AccessibilityConnection,
+) -> Option<FocusedAccessibleDetails> {
+    let desktop = connection.root_accessible_on_registry().await.ok()?;
+    let top_level_application_count = desktop.child_count().await.ok()?;
+
+    for application_index in 0..top_level_application_count {
+        let application_accessible = desktop.get_child_at_index(application_index).await.ok()?;
+        let Some(focused_accessible) =
+            find_focused_descendant(&application_accessible, MAX_FOCUS_SEARCH_DEPTH).await
+        else {
+            continue;
+        };
+
+        let application_name = application_accessible.name().await.ok();
+        let window_title = focused_accessible
+            .get_application()
+            .await
+            .ok()
+            .and(focused_accessible.name().await.ok());
+        let role = focused_accessible.get_role().await.ok()?;
+        let name = focused_accessible.name().await.ok();
+
+        let is_editable = accessible_role_is_editable_text(role);
+        let is_secure = accessible_role_is_secure_text(role);
+        let (editable_text, selected_text, selection_range, character_count) =
+            if is_editable && !is_secure {
+                let editable_text = focused_accessible.get_text(0, -1).await.ok();
+                let selection_range = focused_accessible
+                    .get_selection(0)
+                    .await
+                    .ok()
+                    .map(|(start, end)| (start.max(0) as usize, end.max(0) as usize));
+                let selected_text = match selection_range {
+                    Some((selection_start, selection_end)) => focused_accessible
+                        .get_text(selection_start as i32, selection_end as i32)
+                        .await
+                        .ok(),
+                    None => None,
+                };
+                let character_count = focused_accessible
+                    .character_count()
+                    .await
+                    .ok()
+                    .map(|count| count.max(0) as usize);
+                (
+                    editable_text,
+                    selected_text,
+                    selection_range,
+                    character_count,
+                )
+            } else {
+                (None, None, None, None)
+            };
+
+        return Some(FocusedAccessibleDetails {
+            application_name,
+            window_title,
+            role,
+            name,
+            editable_text,
+            selected_text,
+            selection_range,
+            character_count,
+        });
+    }
+
+    None
+}
+
+fn get_focused_accessible_details() -> Option<FocusedAccessibleDetails> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .ok()?;
+
+    runtime.block_on(async {
+        let connection = AccessibilityConnection::new().await.ok()?;
+        collect_focused_accessible_details(&connection).await
+    })
+}
+
+fn build_focused_text_element(
+    focused_accessible_details: &FocusedAccessibleDetails,
+) -> FocusedTextElement {
+    let role_name = format!("{:?}", focused_accessible_details.role);
+    let is_secure = accessible_role_is_secure_text(focused_accessible_details.role);
+    let is_editable = accessible_role_is_editable_text(focused_accessible_details.role);
+
+    if is_secure {
+        return FocusedTextElement {
+            role: role_name,
+            is_editable: true,
+            is_secure: true,
+            selected_text: None,
+            selection_range: None,
+            character_count: None,
+        };
+    }
+
+    FocusedTextElement {
+        role: role_name,
+        is_editable,
+        is_secure: false,
+        selected_text: focused_accessible_details
+            .selected_text
+            .as_deref()
+            .and_then(normalize_non_empty_focus_text),
+        selection_range: focused_accessible_details.selection_range,
+        character_count: focused_accessible_details.character_count,
+    }
+}
+
+pub fn get_current_active_app_context() -> ActiveAppContextSnapshot {
+    let captured_at = chrono::Utc::now().to_rfc3339();
+
+    let Some(focused_accessible_details) = get_focused_accessible_details() else {
+        return ActiveAppContextSnapshot {
+            focused_application: None,
+            focused_window: None,
+            focused_browser_tab: None,
+            focused_text_element: None,
+            event_source: FocusEventSource::Polling,
+            confidence_level: FocusConfidenceLevel::Low,
+            captured_at,
+        };
+    };
+
+    let focused_application =
+        focused_accessible_details
+            .application_name
+            .as_ref()
+            .map(|application_name| FocusedApplication {
+                display_name: application_name.clone(),
+                bundle_id: None,
+                process_path: None,
+            });
+    let window_title = focused_accessible_details
+        .window_title
+        .as_deref()
+        .and_then(normalize_non_empty_focus_text)
+        .or_else(|| focused_accessible_details.name.clone());
+    let focused_window = window_title.as_ref().map(|window_title| FocusedWindow {
+        title: window_title.clone(),
+    });
+
+    let supported_browser = focused_application
+        .as_ref()
+        .and_then(|focused_application| {
+            supported_browser_from_application_name(&focused_application.display_name)
+        });
+    let browser_tab_title = supported_browser.and_then(|supported_browser| {
+        infer_browser_tab_title_from_window_title(
+            window_title.as_deref(),
+            supported_browser.display_name(),
+        )
+    });
+    let browser_document_origin = focused_accessible_details
+        .editable_text
+        .as_deref()
+        .and_then(normalize_browser_document_origin);
+    let focused_browser_tab = supported_browser.and_then(|supported_browser| {
+        if browser_tab_title.is_none() && browser_document_origin.is_none() {
+            return None;
+        }
+
+        Some(FocusedBrowserTab {
+            title: browser_tab_title,
+            origin: browser_document_origin,
+            browser: Some(supported_browser.display_name().to_string()),
+        })
+    });
+    let focused_browser_origin_is_present = focused_browser_tab
+        .as_ref()
+        .and_then(|focused_browser_tab| focused_browser_tab.origin.as_ref())
+        .is_some();
+    let focused_text_element = build_focused_text_element(&focused_accessible_details);
+    let focused_text_element_is_secure = focused_text_element.is_secure;
+
+    let confidence_level = determine_focus_confidence_level(
+        focused_window.is_some(),
+        focused_browser_tab.is_some(),
+        focused_browser_origin_is_present,
+        focused_text_element_is_secure,
+    );
+
+    ActiveAppContextSnapshot {
+        focused_application,
+        focused_window,
+        focused_browser_tab,
+        focused_text_element: Some(focused_text_element),
+        event_source: FocusEventSource::Accessibility,
+        confidence_level,
+        captured_at,
+    }
+}
+
+#[cfg(test)]
+#[path = "../tests/active_app_context_linux_tests.rs"]
+mod focus_linux_tests;
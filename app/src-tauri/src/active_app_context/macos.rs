@@ -1,17 +1,25 @@
+use std::collections::VecDeque;
 use std::ffi::c_void;
+use std::process::Command;
 use std::ptr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use block2::RcBlock;
+use core_foundation::array::CFArray;
 use core_foundation::base::{CFType, CFTypeID, CFTypeRef, TCFType};
 use core_foundation::dictionary::CFDictionary;
 use core_foundation::number::CFNumber;
 use core_foundation::string::{CFString, CFStringRef};
+use core_foundation::url::CFURL;
 use core_graphics::window::{
     copy_window_info, kCGNullWindowID, kCGWindowLayer, kCGWindowListExcludeDesktopElements,
     kCGWindowListOptionOnScreenOnly, kCGWindowName, kCGWindowOwnerPID,
 };
 use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
 use objc2_app_kit::{NSRunningApplication, NSWorkspace};
-use objc2_foundation::NSString;
+use objc2_foundation::{NSNotification, NSNotificationCenter, NSString};
 
 use super::shared::{
     determine_focus_confidence_level, infer_browser_tab_title_from_window_title,
@@ -19,7 +27,7 @@ use super::shared::{
 };
 use crate::active_app_context::{
     ActiveAppContextSnapshot, FocusEventSource, FocusedApplication, FocusedBrowserTab,
-    FocusedWindow, SupportedBrowser,
+    FocusedTextElement, FocusedWindow, SupportedBrowser,
 };
 
 type AccessibilityUiElementRef = *const c_void;
@@ -27,6 +35,26 @@ type AccessibilityError = i32;
 
 const ACCESSIBILITY_SUCCESS: AccessibilityError = 0;
 
+/// `AXValueGetValue`'s `kAXValueCFRangeType` - the one `AXValueType` we need
+/// to unwrap an `AXSelectedTextRange`, which comes back as an opaque
+/// `AXValue` rather than a plain `CFRange`.
+const AX_VALUE_CF_RANGE_TYPE: u32 = 4;
+
+/// Mirrors CoreFoundation's `CFRange` layout (`CFIndex location, length`)
+/// for use with `AXValueGetValue(..., kAXValueCFRangeType, ...)`.
+#[repr(C)]
+struct AXCFRange {
+    location: isize,
+    length: isize,
+}
+
+const EDITABLE_TEXT_ACCESSIBILITY_ROLES: [&str; 3] = ["AXTextField", "AXTextArea", "AXComboBox"];
+const SECURE_TEXT_FIELD_ACCESSIBILITY_ROLE: &str = "AXSecureTextField";
+const WEB_AREA_ACCESSIBILITY_ROLE: &str = "AXWebArea";
+const MAX_ANCESTOR_WALK_DEPTH: u8 = 25;
+const WEB_AREA_URL_SEARCH_MAX_DEPTH: u8 = 6;
+const WEB_AREA_URL_SEARCH_MAX_NODES: usize = 200;
+
 #[link(name = "ApplicationServices", kind = "framework")]
 unsafe extern "C" {
     fn AXIsProcessTrusted() -> bool;
@@ -37,6 +65,7 @@ unsafe extern "C" {
         attribute: CFStringRef,
         value: *mut CFTypeRef,
     ) -> AccessibilityError;
+    fn AXValueGetValue(value: CFTypeRef, value_type: u32, value_out: *mut c_void) -> bool;
 }
 
 #[derive(Debug, Clone)]
@@ -50,6 +79,7 @@ struct FrontmostApplicationMetadata {
 struct AccessibilityFocusedWindowDetails {
     focused_window_title: Option<String>,
     focused_document_url: Option<String>,
+    focused_text_element: Option<FocusedTextElement>,
 }
 
 fn get_frontmost_application() -> Option<Retained<NSRunningApplication>> {
@@ -151,6 +181,161 @@ fn get_focused_window_accessibility_element(
         })
 }
 
+fn is_editable_text_accessibility_role(role: &str) -> bool {
+    EDITABLE_TEXT_ACCESSIBILITY_ROLES.contains(&role)
+}
+
+fn is_descendant_of_web_area(element: &CFType) -> bool {
+    let mut current_ancestor = copy_accessibility_element_attribute_value(element, "AXParent");
+    for _ in 0..MAX_ANCESTOR_WALK_DEPTH {
+        let Some(ancestor) = current_ancestor else {
+            return false;
+        };
+        if copy_accessibility_string_attribute_value(&ancestor, "AXRole").as_deref()
+            == Some(WEB_AREA_ACCESSIBILITY_ROLE)
+        {
+            return true;
+        }
+        current_ancestor = copy_accessibility_element_attribute_value(&ancestor, "AXParent");
+    }
+    false
+}
+
+fn copy_accessibility_selected_text_range(element: &CFType) -> Option<(usize, usize)> {
+    let selected_text_range_value =
+        copy_accessibility_attribute_value(element, "AXSelectedTextRange")?;
+    let mut cf_range = AXCFRange {
+        location: 0,
+        length: 0,
+    };
+    let value_was_extracted = unsafe {
+        AXValueGetValue(
+            selected_text_range_value.as_CFTypeRef(),
+            AX_VALUE_CF_RANGE_TYPE,
+            &raw mut cf_range as *mut c_void,
+        )
+    };
+    if !value_was_extracted || cf_range.location < 0 || cf_range.length < 0 {
+        return None;
+    }
+
+    let selection_start = cf_range.location as usize;
+    let selection_end = selection_start + cf_range.length as usize;
+    Some((selection_start, selection_end))
+}
+
+fn copy_accessibility_character_count(element: &CFType) -> Option<usize> {
+    let character_count_value =
+        copy_accessibility_attribute_value(element, "AXNumberOfCharacters")?;
+    let character_count_number = character_count_value.downcast::<CFNumber>()?;
+    usize::try_from(character_count_number.to_i32()?).ok()
+}
+
+/// Build a `FocusedTextElement` for `focused_ui_element`. Password fields
+/// (`AXSecureTextField`) are reported as editable but never have their
+/// value, selection, or character count read - that content must never be
+/// surfaced to a dictation caller.
+fn build_focused_text_element(focused_ui_element: &CFType) -> Option<FocusedTextElement> {
+    let role = copy_accessibility_string_attribute_value(focused_ui_element, "AXRole")?;
+
+    if role == SECURE_TEXT_FIELD_ACCESSIBILITY_ROLE {
+        return Some(FocusedTextElement {
+            role,
+            is_editable: true,
+            is_secure: true,
+            selected_text: None,
+            selection_range: None,
+            character_count: None,
+        });
+    }
+
+    let is_editable =
+        is_editable_text_accessibility_role(&role) || is_descendant_of_web_area(focused_ui_element);
+    if !is_editable {
+        return Some(FocusedTextElement {
+            role,
+            is_editable: false,
+            is_secure: false,
+            selected_text: None,
+            selection_range: None,
+            character_count: None,
+        });
+    }
+
+    Some(FocusedTextElement {
+        role,
+        is_editable: true,
+        is_secure: false,
+        selected_text: copy_accessibility_string_attribute_value(
+            focused_ui_element,
+            "AXSelectedText",
+        ),
+        selection_range: copy_accessibility_selected_text_range(focused_ui_element),
+        character_count: copy_accessibility_character_count(focused_ui_element),
+    })
+}
+
+fn copy_accessibility_url_attribute_value(
+    accessibility_element: &CFType,
+    attribute_name: &str,
+) -> Option<String> {
+    let accessibility_attribute_value =
+        copy_accessibility_attribute_value(accessibility_element, attribute_name)?;
+    let accessibility_attribute_url = accessibility_attribute_value.downcast::<CFURL>()?;
+    normalize_non_empty_focus_text(&accessibility_attribute_url.get_string().to_string())
+}
+
+fn copy_accessibility_children(accessibility_element: &CFType) -> Vec<CFType> {
+    let Some(children_attribute_value) =
+        copy_accessibility_attribute_value(accessibility_element, "AXChildren")
+    else {
+        return Vec::new();
+    };
+    let Some(children_array) = children_attribute_value.downcast::<CFArray<CFType>>() else {
+        return Vec::new();
+    };
+    children_array.iter().map(|child| child.clone()).collect()
+}
+
+/// Breadth-first search of the accessibility tree rooted at `focused_window`
+/// for the first `AXWebArea` with a usable URL. `AXDocument` on the window
+/// itself only works for Safari/WebKit; Firefox and Chromium-family browsers
+/// put the URL on a nested web-content element instead, so we walk down to
+/// find it. Bounded by both depth and total node count so a pathological
+/// window (deeply nested or DOM-backed tree) can't make a single snapshot
+/// call run away.
+fn find_browser_document_url_in_subtree(focused_window: &CFType) -> Option<String> {
+    let mut search_queue: VecDeque<(CFType, u8)> = VecDeque::new();
+    search_queue.push_back((focused_window.clone(), 0));
+    let mut visited_node_count = 0usize;
+
+    while let Some((element, depth)) = search_queue.pop_front() {
+        if visited_node_count >= WEB_AREA_URL_SEARCH_MAX_NODES {
+            break;
+        }
+        visited_node_count += 1;
+
+        if copy_accessibility_string_attribute_value(&element, "AXRole").as_deref()
+            == Some(WEB_AREA_ACCESSIBILITY_ROLE)
+        {
+            let web_area_url = copy_accessibility_url_attribute_value(&element, "AXURL")
+                .or_else(|| copy_accessibility_string_attribute_value(&element, "AXDocument"));
+            if web_area_url.is_some() {
+                return web_area_url;
+            }
+        }
+
+        if depth >= WEB_AREA_URL_SEARCH_MAX_DEPTH {
+            continue;
+        }
+        for child in copy_accessibility_children(&element) {
+            search_queue.push_back((child, depth + 1));
+        }
+    }
+
+    None
+}
+
 fn get_accessibility_focused_window_details(
     process_identifier: i32,
 ) -> Option<AccessibilityFocusedWindowDetails> {
@@ -158,6 +343,13 @@ fn get_accessibility_focused_window_details(
         create_accessibility_application_element(process_identifier)?;
     let focused_window_accessibility_element =
         get_focused_window_accessibility_element(&application_accessibility_element)?;
+    let focused_ui_element = copy_accessibility_element_attribute_value(
+        &application_accessibility_element,
+        "AXFocusedUIElement",
+    );
+    let focused_text_element = focused_ui_element
+        .as_ref()
+        .and_then(build_focused_text_element);
 
     Some(AccessibilityFocusedWindowDetails {
         focused_window_title: copy_accessibility_string_attribute_value(
@@ -167,7 +359,9 @@ fn get_accessibility_focused_window_details(
         focused_document_url: copy_accessibility_string_attribute_value(
             &focused_window_accessibility_element,
             "AXDocument",
-        ),
+        )
+        .or_else(|| find_browser_document_url_in_subtree(&focused_window_accessibility_element)),
+        focused_text_element,
     })
 }
 
@@ -296,6 +490,90 @@ fn determine_focused_window_title(
         })
 }
 
+/// How long a scripted active-tab URL fetch is trusted before we ask the
+/// browser again - long enough that rapid focus-change polling doesn't spam
+/// it with AppleScript calls, short enough that a tab switch is reflected
+/// within roughly one recording.
+const ACTIVE_TAB_URL_SCRIPT_CACHE_TTL: Duration = Duration::from_millis(1500);
+
+struct CachedActiveTabUrl {
+    supported_browser: SupportedBrowser,
+    fetched_at: Instant,
+    active_tab_url: Option<String>,
+}
+
+static ACTIVE_TAB_URL_SCRIPT_CACHE: Mutex<Option<CachedActiveTabUrl>> = Mutex::new(None);
+
+/// The AppleScript/JXA one-liner that asks `supported_browser` for its
+/// active tab's URL, or `None` for browsers with no scriptable tab dictionary
+/// (e.g. Firefox), which fall back to window-title inference entirely.
+fn apple_script_for_active_tab_url(supported_browser: SupportedBrowser) -> Option<&'static str> {
+    match supported_browser {
+        SupportedBrowser::Safari => {
+            Some(r#"tell application "Safari" to get URL of front document"#)
+        }
+        SupportedBrowser::GoogleChrome => {
+            Some(r#"tell application "Google Chrome" to get URL of active tab of front window"#)
+        }
+        SupportedBrowser::MicrosoftEdge => {
+            Some(r#"tell application "Microsoft Edge" to get URL of active tab of front window"#)
+        }
+        SupportedBrowser::BraveBrowser => {
+            Some(r#"tell application "Brave Browser" to get URL of active tab of front window"#)
+        }
+        SupportedBrowser::Arc => {
+            Some(r#"tell application "Arc" to get URL of active tab of front window"#)
+        }
+        SupportedBrowser::Opera => {
+            Some(r#"tell application "Opera" to get URL of active tab of front window"#)
+        }
+        SupportedBrowser::Vivaldi => {
+            Some(r#"tell application "Vivaldi" to get URL of active tab of front window"#)
+        }
+        SupportedBrowser::Chromium => {
+            Some(r#"tell application "Chromium" to get URL of active tab of front window"#)
+        }
+        SupportedBrowser::Firefox => None,
+    }
+}
+
+/// Run an AppleScript one-liner via `osascript` and return its trimmed
+/// stdout, or `None` if it failed (e.g. the browser denied automation
+/// permission, or has no window open).
+fn run_apple_script(script: &str) -> Option<String> {
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    normalize_non_empty_focus_text(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Fetch `supported_browser`'s active tab URL, reusing a recent result
+/// instead of shelling out to `osascript` on every focus-change poll.
+fn fetch_active_tab_url_cached(supported_browser: SupportedBrowser) -> Option<String> {
+    let mut cache = ACTIVE_TAB_URL_SCRIPT_CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref() {
+        if cached.supported_browser == supported_browser
+            && cached.fetched_at.elapsed() < ACTIVE_TAB_URL_SCRIPT_CACHE_TTL
+        {
+            return cached.active_tab_url.clone();
+        }
+    }
+
+    let active_tab_url =
+        apple_script_for_active_tab_url(supported_browser).and_then(run_apple_script);
+    *cache = Some(CachedActiveTabUrl {
+        supported_browser,
+        fetched_at: Instant::now(),
+        active_tab_url: active_tab_url.clone(),
+    });
+    active_tab_url
+}
+
 fn build_focused_browser_tab(
     frontmost_application_metadata: Option<&FrontmostApplicationMetadata>,
     focused_window_title: Option<&str>,
@@ -308,9 +586,21 @@ fn build_focused_browser_tab(
                 .as_deref()
                 .and_then(supported_browser_from_bundle_identifier)
         })?;
-    let normalized_browser_document_origin = accessibility_focused_window_details
-        .and_then(|focused_window_details| focused_window_details.focused_document_url.as_deref())
-        .and_then(normalize_browser_document_origin);
+    // Prefer the browser's own report of its active tab URL over the
+    // accessibility-tree `AXURL`/`AXDocument` lookup, which only works for
+    // WebKit/Chromium content and not the browser chrome itself; fall back
+    // to the accessibility lookup when scripting is unavailable (automation
+    // permission denied, no window, or an unscriptable browser like Firefox).
+    let normalized_browser_document_origin = fetch_active_tab_url_cached(supported_browser)
+        .as_deref()
+        .and_then(normalize_browser_document_origin)
+        .or_else(|| {
+            accessibility_focused_window_details
+                .and_then(|focused_window_details| {
+                    focused_window_details.focused_document_url.as_deref()
+                })
+                .and_then(normalize_browser_document_origin)
+        });
     let inferred_browser_tab_title = infer_browser_tab_title_from_window_title(
         focused_window_title,
         supported_browser.display_name(),
@@ -326,6 +616,245 @@ fn build_focused_browser_tab(
     })
 }
 
+type AXObserverRef = *const c_void;
+type AXObserverCallback =
+    extern "C" fn(AXObserverRef, AccessibilityUiElementRef, CFStringRef, *mut c_void);
+type CFRunLoopRef = *const c_void;
+type CFRunLoopSourceRef = *const c_void;
+
+#[link(name = "ApplicationServices", kind = "framework")]
+unsafe extern "C" {
+    fn AXObserverCreate(
+        application: i32,
+        callback: AXObserverCallback,
+        observer: *mut AXObserverRef,
+    ) -> AccessibilityError;
+    fn AXObserverAddNotification(
+        observer: AXObserverRef,
+        element: AccessibilityUiElementRef,
+        notification: CFStringRef,
+        refcon: *mut c_void,
+    ) -> AccessibilityError;
+    fn AXObserverGetRunLoopSource(observer: AXObserverRef) -> CFRunLoopSourceRef;
+
+    static kAXFocusedWindowChangedNotification: CFStringRef;
+    static kAXFocusedUIElementChangedNotification: CFStringRef;
+    static kAXTitleChangedNotification: CFStringRef;
+    static kAXValueChangedNotification: CFStringRef;
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+unsafe extern "C" {
+    fn CFRunLoopGetCurrent() -> CFRunLoopRef;
+    fn CFRunLoopAddSource(run_loop: CFRunLoopRef, source: CFRunLoopSourceRef, mode: CFStringRef);
+    fn CFRunLoopRemoveSource(run_loop: CFRunLoopRef, source: CFRunLoopSourceRef, mode: CFStringRef);
+    static kCFRunLoopDefaultMode: CFStringRef;
+}
+
+/// The handful of AX notifications that fire when the focused window, the
+/// focused UI element within it, or that element's title/value changes -
+/// everything `get_current_active_app_context` would otherwise have had to
+/// be polled for.
+fn observed_accessibility_notifications() -> [CFStringRef; 4] {
+    unsafe {
+        [
+            kAXFocusedWindowChangedNotification,
+            kAXFocusedUIElementChangedNotification,
+            kAXTitleChangedNotification,
+            kAXValueChangedNotification,
+        ]
+    }
+}
+
+extern "C" fn handle_ax_notification(
+    _observer: AXObserverRef,
+    _element: AccessibilityUiElementRef,
+    _notification: CFStringRef,
+    refcon: *mut c_void,
+) {
+    // SAFETY: `refcon` was produced by `Box::into_raw` in `attach_ax_observer`
+    // and stays alive for as long as the `AttachedAxObserver` that owns it.
+    let on_notification = unsafe { &*(refcon as *const Box<dyn Fn() + Send + Sync>) };
+    on_notification();
+}
+
+/// An `AXObserver` registered on one app's `AXUIElement`, with its run-loop
+/// source attached to the current thread's run loop. Dropping it detaches
+/// the source from that same run loop (whichever thread drops it) and
+/// releases the observer and its callback state.
+struct AttachedAxObserver {
+    observer: CFType,
+    run_loop: CFRunLoopRef,
+    run_loop_source: CFRunLoopSourceRef,
+    on_notification_callback: *mut Box<dyn Fn() + Send + Sync>,
+}
+
+// SAFETY: the only mutable state reachable through `AttachedAxObserver` is
+// the boxed callback, which itself is `Send + Sync`; the raw CF pointers it
+// wraps are never read concurrently from more than one thread at a time in
+// our usage (always either the run loop's thread or `Drop`).
+unsafe impl Send for AttachedAxObserver {}
+
+impl Drop for AttachedAxObserver {
+    fn drop(&mut self) {
+        unsafe {
+            CFRunLoopRemoveSource(
+                self.run_loop,
+                self.run_loop_source,
+                kCFRunLoopDefaultMode,
+            );
+            drop(Box::from_raw(self.on_notification_callback));
+        }
+    }
+}
+
+/// Create an `AXObserver` for `process_identifier`, subscribe it to
+/// `observed_accessibility_notifications`, and attach its run-loop source to
+/// the current run loop. Must be called on a thread that is actually
+/// pumping a run loop (the app's main thread), or the observer will never
+/// fire.
+fn attach_ax_observer(
+    process_identifier: i32,
+    on_notification: impl Fn() + Send + Sync + 'static,
+) -> Option<AttachedAxObserver> {
+    let application_accessibility_element =
+        create_accessibility_application_element(process_identifier)?;
+
+    let mut observer_ref: AXObserverRef = ptr::null();
+    let create_status = unsafe {
+        AXObserverCreate(
+            process_identifier,
+            handle_ax_notification,
+            &raw mut observer_ref,
+        )
+    };
+    if create_status != ACCESSIBILITY_SUCCESS || observer_ref.is_null() {
+        return None;
+    }
+    let observer = unsafe { CFType::wrap_under_create_rule(observer_ref as CFTypeRef) };
+
+    let on_notification_callback: *mut Box<dyn Fn() + Send + Sync> =
+        Box::into_raw(Box::new(Box::new(on_notification)));
+
+    let application_ui_element =
+        application_accessibility_element.as_CFTypeRef() as AccessibilityUiElementRef;
+    for notification_name in observed_accessibility_notifications() {
+        unsafe {
+            AXObserverAddNotification(
+                observer_ref,
+                application_ui_element,
+                notification_name,
+                on_notification_callback as *mut c_void,
+            );
+        }
+    }
+
+    let run_loop_source = unsafe { AXObserverGetRunLoopSource(observer_ref) };
+    let run_loop = unsafe { CFRunLoopGetCurrent() };
+    unsafe {
+        CFRunLoopAddSource(run_loop, run_loop_source, kCFRunLoopDefaultMode);
+    }
+
+    Some(AttachedAxObserver {
+        observer,
+        run_loop,
+        run_loop_source,
+        on_notification_callback,
+    })
+}
+
+/// Re-point `attached_observer` at whatever app is currently frontmost and
+/// emit a fresh snapshot for it. Called once up front and again every time
+/// `NSWorkspace` reports an app activation - if this doesn't run on
+/// activation, the old observer keeps watching the app that just lost
+/// focus and every subsequent event is silently dropped.
+fn reattach_ax_observer_to_frontmost_app(
+    attached_observer: &Mutex<Option<AttachedAxObserver>>,
+    on_snapshot_change: &Arc<dyn Fn(ActiveAppContextSnapshot) + Send + Sync>,
+) {
+    let Some(frontmost_application_metadata) = collect_frontmost_application_metadata() else {
+        *attached_observer.lock().unwrap() = None;
+        return;
+    };
+
+    let emit_current_snapshot = {
+        let on_snapshot_change = on_snapshot_change.clone();
+        move || on_snapshot_change(get_current_active_app_context())
+    };
+
+    *attached_observer.lock().unwrap() = attach_ax_observer(
+        frontmost_application_metadata.process_identifier,
+        emit_current_snapshot.clone(),
+    );
+
+    // Emit immediately so callers see the newly-focused app without waiting
+    // for its first AX notification.
+    emit_current_snapshot();
+}
+
+/// Handle for the event-driven focus watcher. Dropping it unsubscribes from
+/// `NSWorkspace` activation notifications and tears down the currently
+/// attached `AXObserver`.
+pub struct EventDrivenFocusWatcherHandle {
+    activation_observer_token: Retained<AnyObject>,
+    attached_observer: Arc<Mutex<Option<AttachedAxObserver>>>,
+}
+
+impl Drop for EventDrivenFocusWatcherHandle {
+    fn drop(&mut self) {
+        unsafe {
+            NSNotificationCenter::defaultCenter().removeObserver(&self.activation_observer_token);
+        }
+        *self.attached_observer.lock().unwrap() = None;
+    }
+}
+
+/// Start event-driven focus tracking: an `AXObserver` on the frontmost app's
+/// `AXUIElement`, re-attached to the new frontmost app's `AXUIElement` on
+/// every `NSWorkspace` activation notification. `on_snapshot_change` is
+/// invoked with a freshly-built `ActiveAppContextSnapshot` on every
+/// observed change; callers feed it through the same debounce pipeline a
+/// polled snapshot would have gone through.
+///
+/// Returns `None` when accessibility access isn't trusted, since AXObserver
+/// registration would silently fail to ever call back - callers should fall
+/// back to polling in that case. Must be called on the app's main thread,
+/// since it attaches a run-loop source to whatever run loop is current.
+pub fn start_event_driven_focus_watcher(
+    on_snapshot_change: impl Fn(ActiveAppContextSnapshot) + Send + Sync + 'static,
+) -> Option<EventDrivenFocusWatcherHandle> {
+    if !is_accessibility_api_trusted() {
+        return None;
+    }
+
+    let on_snapshot_change: Arc<dyn Fn(ActiveAppContextSnapshot) + Send + Sync> =
+        Arc::new(on_snapshot_change);
+    let attached_observer: Arc<Mutex<Option<AttachedAxObserver>>> = Arc::new(Mutex::new(None));
+
+    reattach_ax_observer_to_frontmost_app(&attached_observer, &on_snapshot_change);
+
+    let activation_block = {
+        let attached_observer = attached_observer.clone();
+        RcBlock::new(move |_notification: std::ptr::NonNull<NSNotification>| {
+            reattach_ax_observer_to_frontmost_app(&attached_observer, &on_snapshot_change);
+        })
+    };
+
+    let activation_observer_token = unsafe {
+        NSNotificationCenter::defaultCenter().addObserverForName_object_queue_usingBlock(
+            Some(&NSWorkspace::didActivateApplicationNotification()),
+            None,
+            None,
+            &activation_block,
+        )
+    };
+
+    Some(EventDrivenFocusWatcherHandle {
+        activation_observer_token,
+        attached_observer,
+    })
+}
+
 pub fn get_current_active_app_context() -> ActiveAppContextSnapshot {
     let captured_at = chrono::Utc::now().to_rfc3339();
     let frontmost_application_metadata = collect_frontmost_application_metadata();
@@ -352,6 +881,12 @@ pub fn get_current_active_app_context() -> ActiveAppContextSnapshot {
         .as_ref()
         .and_then(|focused_browser_tab| focused_browser_tab.origin.as_ref())
         .is_some();
+    let focused_text_element = accessibility_focused_window_details
+        .as_ref()
+        .and_then(|details| details.focused_text_element.clone());
+    let focused_text_element_is_secure = focused_text_element
+        .as_ref()
+        .is_some_and(|text_element| text_element.is_secure);
     let event_source = if accessibility_focused_window_details.is_some() {
         FocusEventSource::Accessibility
     } else {
@@ -361,12 +896,14 @@ pub fn get_current_active_app_context() -> ActiveAppContextSnapshot {
         focused_window.is_some(),
         focused_browser_tab.is_some(),
         focused_browser_origin_is_present,
+        focused_text_element_is_secure,
     );
 
     ActiveAppContextSnapshot {
         focused_application,
         focused_window,
         focused_browser_tab,
+        focused_text_element,
         event_source,
         confidence_level,
         captured_at,
@@ -2,9 +2,10 @@ use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 
 #[cfg_attr(
-    not(any(target_os = "windows", target_os = "macos", test)),
+    not(any(target_os = "windows", target_os = "macos", target_os = "linux", test)),
     allow(dead_code)
 )]
+mod redaction;
 mod shared;
 mod watcher;
 
@@ -14,9 +15,19 @@ mod macos;
 #[cfg(target_os = "windows")]
 mod windows;
 
+#[cfg(target_os = "linux")]
+mod linux;
+
+pub use redaction::{FocusRedactionAction, FocusRedactionRule};
 pub use watcher::{start_focus_watcher, FocusWatcherHandle};
 
-#[cfg_attr(not(any(target_os = "windows", target_os = "macos")), allow(dead_code))]
+#[cfg(target_os = "windows")]
+pub(crate) use windows::{foreground_window_process_id, process_display_name_for_pid};
+
+#[cfg_attr(
+    not(any(target_os = "windows", target_os = "macos", target_os = "linux")),
+    allow(dead_code)
+)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SupportedBrowser {
     #[cfg_attr(target_os = "windows", allow(dead_code))]
@@ -31,7 +42,10 @@ pub enum SupportedBrowser {
     Chromium,
 }
 
-#[cfg_attr(not(any(target_os = "windows", target_os = "macos")), allow(dead_code))]
+#[cfg_attr(
+    not(any(target_os = "windows", target_os = "macos", target_os = "linux")),
+    allow(dead_code)
+)]
 impl SupportedBrowser {
     pub fn display_name(self) -> &'static str {
         match self {
@@ -72,6 +86,18 @@ pub struct FocusedApplication {
     pub process_path: Option<String>,
 }
 
+impl FocusedApplication {
+    /// A stable identifier for this app to key per-app settings on: the
+    /// bundle id on macOS, the executable path on Windows, falling back to
+    /// the display name where neither is available.
+    pub fn identifier(&self) -> &str {
+        self.bundle_id
+            .as_deref()
+            .or(self.process_path.as_deref())
+            .unwrap_or(&self.display_name)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FocusedWindow {
     pub title: String,
@@ -84,11 +110,40 @@ pub struct FocusedBrowserTab {
     pub browser: Option<String>,
 }
 
+/// The accessibility element that currently has keyboard focus, with enough
+/// detail for a dictation caller to decide whether and where it can insert
+/// text. `selected_text`/`selection_range`/`character_count` are left `None`
+/// for non-editable elements, and are always `None` when `is_secure` is
+/// `true` (password fields) even though those are reported as `is_editable` -
+/// callers should not insert text based on `is_editable` alone without also
+/// checking `is_secure`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FocusedTextElement {
+    pub role: String,
+    pub is_editable: bool,
+    pub is_secure: bool,
+    pub selected_text: Option<String>,
+    pub selection_range: Option<(usize, usize)>,
+    pub character_count: Option<usize>,
+}
+
+/// Whether the dictation pipeline should refuse to auto-paste into whatever
+/// is currently focused, e.g. because it's a password field. Callers that
+/// can't otherwise see an `ActiveAppContextSnapshot` (like a one-shot
+/// `type_text` command invocation) should check this before pasting.
+pub fn snapshot_blocks_autopaste(snapshot: &ActiveAppContextSnapshot) -> bool {
+    snapshot
+        .focused_text_element
+        .as_ref()
+        .is_some_and(|focused_text_element| focused_text_element.is_secure)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ActiveAppContextSnapshot {
     pub focused_application: Option<FocusedApplication>,
     pub focused_window: Option<FocusedWindow>,
     pub focused_browser_tab: Option<FocusedBrowserTab>,
+    pub focused_text_element: Option<FocusedTextElement>,
     pub event_source: FocusEventSource,
     pub confidence_level: FocusConfidenceLevel,
     pub captured_at: String,
@@ -103,12 +158,17 @@ pub fn get_current_active_app_context() -> ActiveAppContextSnapshot {
     {
         macos::get_current_active_app_context()
     }
-    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[cfg(target_os = "linux")]
+    {
+        linux::get_current_active_app_context()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
         ActiveAppContextSnapshot {
             focused_application: None,
             focused_window: None,
             focused_browser_tab: None,
+            focused_text_element: None,
             event_source: FocusEventSource::Unknown,
             confidence_level: FocusConfidenceLevel::Low,
             captured_at: chrono::Utc::now().to_rfc3339(),
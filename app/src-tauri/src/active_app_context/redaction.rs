@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+
+use super::ActiveAppContextSnapshot;
+
+const REDACTED_PLACEHOLDER: &str = "[redacted]";
+
+/// What to do with a snapshot whose application, window title, or browser
+/// origin matched a [`FocusRedactionRule`]'s pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusRedactionAction {
+    /// Replace window/tab titles and browser origin with a placeholder and
+    /// drop any selected text, keeping only app identity (display name/bundle id) -
+    /// enough for per-app hotkey profiles to still work, but not enough to
+    /// key behavior on a specific site. Use [`Self::OriginOnly`] instead if
+    /// the origin itself needs to survive.
+    Redact,
+    /// Drop the snapshot entirely - nothing is emitted for this poll.
+    Suppress,
+    /// Keep the browser origin but strip window/tab titles and selected
+    /// text, e.g. for a host you trust enough to key per-app behavior on
+    /// but whose page titles may be sensitive.
+    OriginOnly,
+}
+
+/// A single rule in the focus-redaction list: if `pattern` matches (case
+/// insensitive, `*`/`?` glob semantics) the focused application's display
+/// name or bundle id, the focused window's title, or the focused browser
+/// tab's origin host, `action` is applied to the snapshot before it's
+/// emitted. Rules are evaluated in order - the first match wins.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FocusRedactionRule {
+    pub pattern: String,
+    pub action: FocusRedactionAction,
+}
+
+/// Match `candidate` against `pattern` case-insensitively, where `*` matches
+/// any run of characters (including none) and `?` matches exactly one
+/// character. Implemented as a linear two-pointer scan that backtracks to
+/// the most recent `*` on a mismatch, rather than pulling in a regex
+/// dependency for what is just glob matching.
+fn glob_pattern_matches(pattern: &str, candidate: &str) -> bool {
+    let pattern_chars: Vec<char> = pattern.to_lowercase().chars().collect();
+    let candidate_chars: Vec<char> = candidate.to_lowercase().chars().collect();
+
+    let mut pattern_index = 0;
+    let mut candidate_index = 0;
+    let mut last_star_pattern_index: Option<usize> = None;
+    let mut last_star_candidate_index = 0;
+
+    while candidate_index < candidate_chars.len() {
+        let current_pattern_char = pattern_chars.get(pattern_index).copied();
+        if current_pattern_char == Some('*') {
+            last_star_pattern_index = Some(pattern_index);
+            last_star_candidate_index = candidate_index;
+            pattern_index += 1;
+        } else if current_pattern_char == Some('?')
+            || current_pattern_char == candidate_chars.get(candidate_index).copied()
+        {
+            pattern_index += 1;
+            candidate_index += 1;
+        } else if let Some(star_pattern_index) = last_star_pattern_index {
+            pattern_index = star_pattern_index + 1;
+            last_star_candidate_index += 1;
+            candidate_index = last_star_candidate_index;
+        } else {
+            return false;
+        }
+    }
+
+    while pattern_chars.get(pattern_index) == Some(&'*') {
+        pattern_index += 1;
+    }
+
+    pattern_index == pattern_chars.len()
+}
+
+fn origin_host(origin: &str) -> &str {
+    origin
+        .find("://")
+        .map(|scheme_separator_index| &origin[scheme_separator_index + 3..])
+        .unwrap_or(origin)
+}
+
+fn snapshot_redaction_match_candidates(snapshot: &ActiveAppContextSnapshot) -> Vec<&str> {
+    let mut candidates = Vec::new();
+    if let Some(focused_application) = snapshot.focused_application.as_ref() {
+        candidates.push(focused_application.display_name.as_str());
+        if let Some(bundle_id) = focused_application.bundle_id.as_deref() {
+            candidates.push(bundle_id);
+        }
+    }
+    if let Some(focused_window) = snapshot.focused_window.as_ref() {
+        candidates.push(focused_window.title.as_str());
+    }
+    if let Some(origin) = snapshot
+        .focused_browser_tab
+        .as_ref()
+        .and_then(|focused_browser_tab| focused_browser_tab.origin.as_deref())
+    {
+        candidates.push(origin_host(origin));
+    }
+    candidates
+}
+
+fn first_matching_rule<'a>(
+    rules: &'a [FocusRedactionRule],
+    snapshot: &ActiveAppContextSnapshot,
+) -> Option<&'a FocusRedactionRule> {
+    let match_candidates = snapshot_redaction_match_candidates(snapshot);
+    rules.iter().find(|rule| {
+        match_candidates
+            .iter()
+            .any(|candidate| glob_pattern_matches(&rule.pattern, candidate))
+    })
+}
+
+fn apply_redaction_action(
+    action: FocusRedactionAction,
+    mut snapshot: ActiveAppContextSnapshot,
+) -> Option<ActiveAppContextSnapshot> {
+    match action {
+        FocusRedactionAction::Suppress => None,
+        FocusRedactionAction::Redact => {
+            if let Some(focused_window) = snapshot.focused_window.as_mut() {
+                focused_window.title = REDACTED_PLACEHOLDER.to_string();
+            }
+            if let Some(focused_browser_tab) = snapshot.focused_browser_tab.as_mut() {
+                focused_browser_tab.title = Some(REDACTED_PLACEHOLDER.to_string());
+                focused_browser_tab.origin = Some(REDACTED_PLACEHOLDER.to_string());
+            }
+            if let Some(focused_text_element) = snapshot.focused_text_element.as_mut() {
+                focused_text_element.selected_text = None;
+                focused_text_element.selection_range = None;
+                focused_text_element.character_count = None;
+            }
+            Some(snapshot)
+        }
+        FocusRedactionAction::OriginOnly => {
+            if let Some(focused_window) = snapshot.focused_window.as_mut() {
+                focused_window.title = REDACTED_PLACEHOLDER.to_string();
+            }
+            if let Some(focused_browser_tab) = snapshot.focused_browser_tab.as_mut() {
+                focused_browser_tab.title = None;
+            }
+            if let Some(focused_text_element) = snapshot.focused_text_element.as_mut() {
+                focused_text_element.selected_text = None;
+                focused_text_element.selection_range = None;
+                focused_text_element.character_count = None;
+            }
+            Some(snapshot)
+        }
+    }
+}
+
+/// Evaluate `rules` against `snapshot` in order and apply the first match's
+/// action. Returns `None` when the matching rule is `Suppress`, meaning the
+/// caller should emit nothing for this snapshot. Snapshots that match no
+/// rule are returned unchanged.
+pub(crate) fn apply_focus_redaction_rules(
+    rules: &[FocusRedactionRule],
+    snapshot: ActiveAppContextSnapshot,
+) -> Option<ActiveAppContextSnapshot> {
+    let Some(matching_rule) = first_matching_rule(rules, &snapshot) else {
+        return Some(snapshot);
+    };
+    apply_redaction_action(matching_rule.action, snapshot)
+}
+
+#[cfg(test)]
+#[path = "../tests/active_app_context_redaction_tests.rs"]
+mod focus_redaction_tests;
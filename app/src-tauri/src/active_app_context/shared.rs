@@ -84,8 +84,14 @@ pub(crate) fn determine_focus_confidence_level(
     focused_window_is_present: bool,
     focused_browser_tab_is_present: bool,
     focused_browser_origin_is_present: bool,
+    focused_text_element_is_secure: bool,
 ) -> FocusConfidenceLevel {
-    if focused_window_is_present && focused_browser_origin_is_present {
+    // A secure text field (e.g. a password box) means downstream callers
+    // must not treat this snapshot as something safe to log or act on, no
+    // matter how confidently we otherwise resolved the window/browser tab.
+    if focused_text_element_is_secure {
+        FocusConfidenceLevel::Low
+    } else if focused_window_is_present && focused_browser_origin_is_present {
         FocusConfidenceLevel::High
     } else if focused_window_is_present || focused_browser_tab_is_present {
         FocusConfidenceLevel::Medium
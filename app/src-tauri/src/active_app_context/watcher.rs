@@ -1,4 +1,3 @@
-#[cfg(target_os = "macos")]
 use std::sync::mpsc;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
@@ -7,12 +6,14 @@ use std::sync::{
 use std::thread;
 use std::time::{Duration, Instant};
 
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 
+use super::redaction::apply_focus_redaction_rules;
 use crate::active_app_context::{
     get_current_active_app_context, ActiveAppContextSnapshot, FocusConfidenceLevel,
 };
 use crate::events::EventName;
+use crate::settings::SettingsManager;
 
 #[derive(Debug, Clone)]
 pub struct FocusWatcherHandle {
@@ -184,6 +185,106 @@ fn get_active_app_context_snapshot_thread_safe(
     Some(get_current_active_app_context())
 }
 
+/// Feed one freshly-observed snapshot through the redaction rules and then
+/// the debounce/dedup state machine, emitting it if it settles into a new
+/// stable state. Shared by both the polling loop and the event-driven
+/// backend (whose snapshots arrive over a channel from its own callbacks) so
+/// redaction is applied uniformly regardless of which backend produced the
+/// snapshot, and so a `Suppress` match never reaches - and never disturbs -
+/// the `ComparableActiveAppContext` the debounce state machine tracks.
+fn feed_snapshot_into_pipeline(
+    app: &AppHandle,
+    focus_watcher_state: &mut FocusWatcherState,
+    snapshot: ActiveAppContextSnapshot,
+    debounce_window: Duration,
+) {
+    let focus_redaction_rules = app
+        .try_state::<SettingsManager>()
+        .and_then(|settings_manager| settings_manager.get().ok())
+        .map(|settings| settings.focus_redaction_rules)
+        .unwrap_or_default();
+    let Some(snapshot) = apply_focus_redaction_rules(&focus_redaction_rules, snapshot) else {
+        return;
+    };
+
+    let watcher_poll_result = process_focus_snapshot_poll(
+        std::mem::replace(
+            focus_watcher_state,
+            FocusWatcherState::AwaitingInitialEmission,
+        ),
+        snapshot,
+        Instant::now(),
+        debounce_window,
+    );
+    *focus_watcher_state = watcher_poll_result.next_state;
+
+    match watcher_poll_result.emission_candidate {
+        Some(emission_candidate)
+            if app
+                .emit(
+                    EventName::ActiveAppContextChanged.as_str(),
+                    &emission_candidate.candidate_snapshot,
+                )
+                .is_ok() =>
+        {
+            *focus_watcher_state = FocusWatcherState::StableEmitted {
+                emitted_context: emission_candidate.candidate_context,
+            };
+        }
+        Some(_) | None => {}
+    }
+}
+
+/// Try to start the event-driven backend (macOS only, and only when
+/// accessibility access is trusted). Registration has to happen on the
+/// main thread since it attaches a run-loop source to whatever run loop is
+/// current, so this dispatches through `run_on_main_thread` and waits for
+/// the result the same way `get_active_app_context_snapshot_thread_safe`
+/// does.
+#[cfg(target_os = "macos")]
+fn try_start_event_driven_backend(
+    app: &AppHandle,
+    snapshot_sender: mpsc::Sender<ActiveAppContextSnapshot>,
+) -> Option<super::macos::EventDrivenFocusWatcherHandle> {
+    let (ready_sender, ready_receiver) =
+        mpsc::sync_channel::<Option<super::macos::EventDrivenFocusWatcherHandle>>(1);
+
+    app.run_on_main_thread(move || {
+        let handle = super::macos::start_event_driven_focus_watcher(move |snapshot| {
+            let _ = snapshot_sender.send(snapshot);
+        });
+        let _ = ready_sender.send(handle);
+    })
+    .ok()?;
+
+    ready_receiver
+        .recv_timeout(Duration::from_millis(500))
+        .ok()?
+}
+
+/// Try to start the event-driven backend (Windows only). Unlike the macOS
+/// backend, registration doesn't need to happen on any particular thread -
+/// `start_event_driven_focus_watcher` spins up its own dedicated STA thread
+/// - so this can call it directly instead of dispatching through
+/// `run_on_main_thread`.
+#[cfg(target_os = "windows")]
+fn try_start_event_driven_backend(
+    _app: &AppHandle,
+    snapshot_sender: mpsc::Sender<ActiveAppContextSnapshot>,
+) -> Option<super::windows::EventDrivenFocusWatcherHandle> {
+    super::windows::start_event_driven_focus_watcher(move |snapshot| {
+        let _ = snapshot_sender.send(snapshot);
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn try_start_event_driven_backend(
+    _app: &AppHandle,
+    _snapshot_sender: mpsc::Sender<ActiveAppContextSnapshot>,
+) -> Option<()> {
+    None
+}
+
 pub fn start_focus_watcher(app: AppHandle) -> FocusWatcherHandle {
     let should_stop = Arc::new(AtomicBool::new(false));
     let should_stop_clone = should_stop.clone();
@@ -193,35 +294,35 @@ pub fn start_focus_watcher(app: AppHandle) -> FocusWatcherHandle {
         let debounce_window = Duration::from_millis(75);
         let mut focus_watcher_state = FocusWatcherState::AwaitingInitialEmission;
 
+        let (snapshot_sender, snapshot_receiver) = mpsc::channel::<ActiveAppContextSnapshot>();
+        let event_driven_backend = try_start_event_driven_backend(&app, snapshot_sender);
+
+        if let Some(_event_driven_backend) = event_driven_backend {
+            // Keep `_event_driven_backend` alive for as long as we're
+            // reading from its channel; dropping it tears down the
+            // platform subscription (the AXObserver and NSWorkspace
+            // subscription on macOS, the UIA focus-changed thread on Windows).
+            while !should_stop_clone.load(Ordering::SeqCst) {
+                match snapshot_receiver.recv_timeout(poll_interval) {
+                    Ok(snapshot) => feed_snapshot_into_pipeline(
+                        &app,
+                        &mut focus_watcher_state,
+                        snapshot,
+                        debounce_window,
+                    ),
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            return;
+        }
+
         while !should_stop_clone.load(Ordering::SeqCst) {
             let Some(snapshot) = get_active_app_context_snapshot_thread_safe(&app) else {
                 thread::sleep(poll_interval);
                 continue;
             };
-            let watcher_poll_result = process_focus_snapshot_poll(
-                focus_watcher_state,
-                snapshot,
-                Instant::now(),
-                debounce_window,
-            );
-            focus_watcher_state = watcher_poll_result.next_state;
-
-            match watcher_poll_result.emission_candidate {
-                Some(emission_candidate)
-                    if app
-                        .emit(
-                            EventName::ActiveAppContextChanged.as_str(),
-                            &emission_candidate.candidate_snapshot,
-                        )
-                        .is_ok() =>
-                {
-                    focus_watcher_state = FocusWatcherState::StableEmitted {
-                        emitted_context: emission_candidate.candidate_context,
-                    };
-                }
-                Some(_) | None => {}
-            }
-
+            feed_snapshot_into_pipeline(&app, &mut focus_watcher_state, snapshot, debounce_window);
             thread::sleep(poll_interval);
         }
     });
@@ -1,8 +1,12 @@
 use std::ffi::OsString;
 use std::os::windows::ffi::OsStringExt;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
 
-use windows::core::{BSTR, PWSTR};
+use windows::core::{implement, BSTR, PWSTR};
 use windows::Win32::Foundation::{CloseHandle, HWND, RPC_E_CHANGED_MODE};
 use windows::Win32::System::Com::{
     CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER,
@@ -14,10 +18,15 @@ use windows::Win32::System::Threading::{
 use windows::Win32::System::Variant::VARIANT;
 use windows::Win32::UI::Accessibility::{
     CUIAutomation8, IUIAutomation, IUIAutomationCondition, IUIAutomationElement,
-    IUIAutomationElementArray, IUIAutomationValuePattern, TreeScope_Subtree,
-    UIA_ControlTypePropertyId, UIA_EditControlTypeId, UIA_ValuePatternId,
+    IUIAutomationElementArray, IUIAutomationFocusChangedEventHandler,
+    IUIAutomationFocusChangedEventHandler_Impl, IUIAutomationTextPattern, IUIAutomationTextRange,
+    IUIAutomationValuePattern, TreeScope_Subtree, UIA_ControlTypePropertyId, UIA_EditControlTypeId,
+    UIA_TextPatternId, UIA_ValuePatternId,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    DispatchMessageW, GetForegroundWindow, GetWindowTextW, PeekMessageW, TranslateMessage, MSG,
+    PM_REMOVE,
 };
-use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW};
 
 use super::shared::{
     determine_focus_confidence_level, infer_browser_tab_title_from_window_title,
@@ -25,7 +34,7 @@ use super::shared::{
 };
 use crate::active_app_context::{
     ActiveAppContextSnapshot, FocusConfidenceLevel, FocusEventSource, FocusedApplication,
-    FocusedBrowserTab, FocusedWindow, SupportedBrowser,
+    FocusedBrowserTab, FocusedTextElement, FocusedWindow, SupportedBrowser,
 };
 
 fn get_foreground_window() -> Option<HWND> {
@@ -47,9 +56,11 @@ fn get_window_title(hwnd: HWND) -> Option<String> {
     Some(String::from_utf16_lossy(&buffer[..window_title_length]))
 }
 
-fn get_process_path(hwnd: HWND) -> Option<String> {
-    const MAX_PROCESS_PATH_UTF16_LENGTH: usize = 32_768;
-
+/// Resolve the process id that owns `hwnd`, or `None` if the window handle
+/// doesn't resolve to one. Shared with `audio_mute` (through
+/// `foreground_window_process_id`) so per-process audio session muting can
+/// target the same process as the focus watcher.
+pub(crate) fn get_window_process_id(hwnd: HWND) -> Option<u32> {
     let mut process_id: u32 = 0;
     unsafe {
         windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId(
@@ -58,8 +69,21 @@ fn get_process_path(hwnd: HWND) -> Option<String> {
         );
     }
     if process_id == 0 {
-        return None;
+        None
+    } else {
+        Some(process_id)
     }
+}
+
+/// Resolve the process id of the currently-focused (foreground) window.
+pub(crate) fn foreground_window_process_id() -> Option<u32> {
+    get_window_process_id(get_foreground_window()?)
+}
+
+/// Resolve the executable path of process `process_id`, or `None` if it
+/// can't be opened/queried (e.g. a protected system process).
+fn process_path_for_pid(process_id: u32) -> Option<String> {
+    const MAX_PROCESS_PATH_UTF16_LENGTH: usize = 32_768;
 
     let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id) };
     let handle = handle.ok()?;
@@ -92,6 +116,10 @@ fn get_process_path(hwnd: HWND) -> Option<String> {
     )
 }
 
+fn get_process_path(hwnd: HWND) -> Option<String> {
+    process_path_for_pid(get_window_process_id(hwnd)?)
+}
+
 fn get_application_display_name(process_path: &str) -> String {
     Path::new(process_path)
         .file_stem()
@@ -100,6 +128,13 @@ fn get_application_display_name(process_path: &str) -> String {
         .to_string()
 }
 
+/// Resolve a human-readable application name for `process_id` (the
+/// executable's file stem), for callers that only have a pid to work with -
+/// e.g. `audio_mute::windows`'s per-session enumeration.
+pub(crate) fn process_display_name_for_pid(process_id: u32) -> Option<String> {
+    process_path_for_pid(process_id).map(|path| get_application_display_name(&path))
+}
+
 fn supported_browser_from_application_name(application_name: &str) -> Option<SupportedBrowser> {
     let normalized_application_name = application_name.to_lowercase();
     match normalized_application_name.as_str() {
@@ -282,6 +317,107 @@ fn extract_normalized_origin_from_edit_control(
         .and_then(normalize_browser_document_origin)
 }
 
+fn get_focused_automation_element(
+    ui_automation_client: &IUIAutomation,
+) -> Option<IUIAutomationElement> {
+    // SAFETY: The UI Automation client is a valid COM interface for this thread's apartment.
+    unsafe { ui_automation_client.GetFocusedElement() }.ok()
+}
+
+fn get_element_current_localized_control_type(
+    automation_element: &IUIAutomationElement,
+) -> Option<String> {
+    // SAFETY: Reading a property from a valid UIA element; UIA enforces COM invariants.
+    unsafe { automation_element.CurrentLocalizedControlType() }
+        .ok()
+        .and_then(bstr_to_non_empty_focus_text)
+}
+
+fn get_element_current_is_password(automation_element: &IUIAutomationElement) -> bool {
+    // SAFETY: Reading a property from a valid UIA element; UIA enforces COM invariants.
+    unsafe { automation_element.CurrentIsPassword() }
+        .map(|is_password| is_password.as_bool())
+        .unwrap_or(false)
+}
+
+fn get_text_pattern_for_element(
+    automation_element: &IUIAutomationElement,
+) -> Option<IUIAutomationTextPattern> {
+    // SAFETY: Pattern retrieval is performed on a UIA element returned by UIA enumeration.
+    unsafe { automation_element.GetCurrentPatternAs(UIA_TextPatternId) }.ok()
+}
+
+fn get_text_pattern_character_count(text_pattern: &IUIAutomationTextPattern) -> Option<usize> {
+    // SAFETY: Document range retrieval and text extraction on a valid text pattern interface.
+    let document_range = unsafe { text_pattern.DocumentRange() }.ok()?;
+    let document_text = unsafe { document_range.GetText(-1) }
+        .ok()
+        .and_then(bstr_to_non_empty_focus_text)?;
+    Some(document_text.chars().count())
+}
+
+fn get_text_pattern_selected_text(text_pattern: &IUIAutomationTextPattern) -> Option<String> {
+    // SAFETY: Selection retrieval and text extraction on a valid text pattern interface.
+    let selection_ranges = unsafe { text_pattern.GetSelection() }.ok()?;
+    let selection_count = unsafe { selection_ranges.Length() }.ok()?;
+    if selection_count <= 0 {
+        return None;
+    }
+    let first_selection_range: IUIAutomationTextRange =
+        unsafe { selection_ranges.GetElement(0) }.ok()?;
+    unsafe { first_selection_range.GetText(-1) }
+        .ok()
+        .and_then(bstr_to_non_empty_focus_text)
+}
+
+/// Build a `FocusedTextElement` for whatever element currently has UIA
+/// keyboard focus. Password fields (`CurrentIsPassword`) are reported as
+/// editable but never have their value or selection read - UIA exposes no
+/// character-offset API comparable to AX's `AXSelectedTextRange`, so
+/// `selection_range` is always `None` here.
+fn build_focused_text_element(ui_automation_client: &IUIAutomation) -> Option<FocusedTextElement> {
+    let focused_automation_element = get_focused_automation_element(ui_automation_client)?;
+    let role = get_element_current_localized_control_type(&focused_automation_element)
+        .unwrap_or_else(|| "unknown".to_string());
+
+    if get_element_current_is_password(&focused_automation_element) {
+        return Some(FocusedTextElement {
+            role,
+            is_editable: true,
+            is_secure: true,
+            selected_text: None,
+            selection_range: None,
+            character_count: None,
+        });
+    }
+
+    let Some(text_pattern) = get_text_pattern_for_element(&focused_automation_element) else {
+        return Some(FocusedTextElement {
+            role,
+            is_editable: false,
+            is_secure: false,
+            selected_text: None,
+            selection_range: None,
+            character_count: None,
+        });
+    };
+
+    Some(FocusedTextElement {
+        role,
+        is_editable: true,
+        is_secure: false,
+        selected_text: get_text_pattern_selected_text(&text_pattern),
+        selection_range: None,
+        character_count: get_text_pattern_character_count(&text_pattern),
+    })
+}
+
+fn get_focused_text_element_from_uia() -> Option<FocusedTextElement> {
+    let (_com_apartment_initialization_guard, ui_automation_client) =
+        create_ui_automation_client()?;
+    build_focused_text_element(&ui_automation_client)
+}
+
 fn extract_browser_document_origin_from_uia(hwnd: HWND) -> Option<String> {
     let (_com_apartment_initialization_guard, ui_automation_client) =
         create_ui_automation_client()?;
@@ -321,20 +457,14 @@ fn extract_browser_document_origin_from_uia(hwnd: HWND) -> Option<String> {
     None
 }
 
-pub fn get_current_active_app_context() -> ActiveAppContextSnapshot {
+/// Build the full snapshot for a window already known to be focused -
+/// shared by the pull-based `get_current_active_app_context` poll path and
+/// the push-based focus-changed event handler below, so both report window
+/// title, process path, and browser-origin detection identically. Callers
+/// differ only in which `FocusEventSource` the result ends up tagged with.
+fn build_active_app_context_snapshot_for_focused_window(hwnd: HWND) -> ActiveAppContextSnapshot {
     let captured_at = chrono::Utc::now().to_rfc3339();
 
-    let Some(hwnd) = get_foreground_window() else {
-        return ActiveAppContextSnapshot {
-            focused_application: None,
-            focused_window: None,
-            focused_browser_tab: None,
-            event_source: FocusEventSource::Polling,
-            confidence_level: FocusConfidenceLevel::Low,
-            captured_at,
-        };
-    };
-
     let window_title = get_window_title(hwnd);
     let process_path = get_process_path(hwnd);
 
@@ -381,6 +511,11 @@ pub fn get_current_active_app_context() -> ActiveAppContextSnapshot {
     } else {
         FocusEventSource::Polling
     };
+    let focused_text_element = get_focused_text_element_from_uia();
+    let focused_text_element_is_secure = focused_text_element
+        .as_ref()
+        .is_some_and(|focused_text_element| focused_text_element.is_secure);
+
     let confidence_level = determine_focus_confidence_level(
         focused_window.is_some(),
         focused_browser_tab.is_some(),
@@ -388,18 +523,198 @@ pub fn get_current_active_app_context() -> ActiveAppContextSnapshot {
             .as_ref()
             .and_then(|focused_browser_tab| focused_browser_tab.origin.as_ref())
             .is_some(),
+        focused_text_element_is_secure,
     );
 
     ActiveAppContextSnapshot {
         focused_application,
         focused_window,
         focused_browser_tab,
+        focused_text_element,
         event_source,
         confidence_level,
         captured_at,
     }
 }
 
+pub fn get_current_active_app_context() -> ActiveAppContextSnapshot {
+    let Some(hwnd) = get_foreground_window() else {
+        return ActiveAppContextSnapshot {
+            focused_application: None,
+            focused_window: None,
+            focused_browser_tab: None,
+            focused_text_element: None,
+            event_source: FocusEventSource::Polling,
+            confidence_level: FocusConfidenceLevel::Low,
+            captured_at: chrono::Utc::now().to_rfc3339(),
+        };
+    };
+
+    build_active_app_context_snapshot_for_focused_window(hwnd)
+}
+
+/// Build a snapshot in response to a UI Automation focus-changed event.
+/// Always tagged `FocusEventSource::Uia` - the event firing at all is
+/// already higher-confidence evidence than the polling path's best-effort
+/// origin lookup, which only earns that tag when it happens to resolve a
+/// browser document origin.
+fn build_active_app_context_snapshot_for_focus_change(hwnd: HWND) -> ActiveAppContextSnapshot {
+    ActiveAppContextSnapshot {
+        event_source: FocusEventSource::Uia,
+        ..build_active_app_context_snapshot_for_focused_window(hwnd)
+    }
+}
+
+/// COM callback object that fires whenever UI Automation's notion of
+/// keyboard focus changes anywhere on the desktop, regardless of which
+/// window or process gained it - the push-based counterpart to polling
+/// `get_current_active_app_context` on a timer. `sender` is deliberately
+/// not used to resolve the target window: focus can land on an element
+/// that isn't itself a top-level window (e.g. a browser's address bar), so
+/// we re-resolve the foreground window the same way the poll path does.
+#[implement(IUIAutomationFocusChangedEventHandler)]
+struct FocusChangedEventHandler {
+    on_snapshot_change: Box<dyn Fn(ActiveAppContextSnapshot) + Send + Sync>,
+}
+
+impl IUIAutomationFocusChangedEventHandler_Impl for FocusChangedEventHandler_Impl {
+    fn HandleFocusChangedEvent(&self, _sender: &IUIAutomationElement) -> windows::core::Result<()> {
+        if let Some(hwnd) = get_foreground_window() {
+            (self.on_snapshot_change)(build_active_app_context_snapshot_for_focus_change(hwnd));
+        }
+        Ok(())
+    }
+}
+
+/// Handle for the event-driven (UI Automation focus-changed) focus watcher.
+/// Dropping it signals the dedicated STA thread to stop its message loop,
+/// which unregisters the focus-changed handler and tears down its COM
+/// apartment before the thread exits.
+pub struct EventDrivenFocusWatcherHandle {
+    should_stop: Arc<AtomicBool>,
+    thread_handle: Option<thread::JoinHandle<()>>,
+}
+
+impl Drop for EventDrivenFocusWatcherHandle {
+    fn drop(&mut self) {
+        self.should_stop.store(true, Ordering::SeqCst);
+        if let Some(thread_handle) = self.thread_handle.take() {
+            let _ = thread_handle.join();
+        }
+    }
+}
+
+/// How long `start_event_driven_focus_watcher` waits for the dedicated STA
+/// thread to report whether it managed to register the focus-changed
+/// handler before giving up and falling back to polling.
+const FOCUS_CHANGED_HANDLER_REGISTRATION_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// How long the message loop sleeps between `PeekMessageW` polls when the
+/// queue is empty - long enough to avoid spinning, short enough that
+/// `should_stop` is noticed promptly.
+const MESSAGE_LOOP_IDLE_SLEEP: Duration = Duration::from_millis(50);
+
+/// Body of the dedicated STA thread: initialize COM, register the
+/// focus-changed handler, report whether that succeeded, then pump a
+/// message loop (required for the STA apartment to dispatch the handler's
+/// callbacks) until `should_stop` is set.
+fn run_focus_changed_event_loop(
+    should_stop: Arc<AtomicBool>,
+    registration_result_sender: mpsc::SyncSender<bool>,
+    on_snapshot_change: impl Fn(ActiveAppContextSnapshot) + Send + Sync + 'static,
+) {
+    let Some((com_apartment_initialization_guard, ui_automation_client)) =
+        create_ui_automation_client()
+    else {
+        let _ = registration_result_sender.send(false);
+        return;
+    };
+
+    let handler: IUIAutomationFocusChangedEventHandler = FocusChangedEventHandler {
+        on_snapshot_change: Box::new(on_snapshot_change),
+    }
+    .into();
+
+    // SAFETY: `ui_automation_client` and `handler` are both valid COM
+    // interfaces owned by this thread for the remainder of the function.
+    let register_result =
+        unsafe { ui_automation_client.AddFocusChangedEventHandler(None, &handler) };
+    if register_result.is_err() {
+        let _ = registration_result_sender.send(false);
+        return;
+    }
+    let _ = registration_result_sender.send(true);
+
+    let mut message = MSG::default();
+    while !should_stop.load(Ordering::SeqCst) {
+        // SAFETY: `message` is a valid, exclusively-owned buffer for the
+        // duration of this call.
+        let has_pending_message =
+            unsafe { PeekMessageW(&mut message, None, 0, 0, PM_REMOVE) }.as_bool();
+        if has_pending_message {
+            // SAFETY: `message` was just populated by the `PeekMessageW` call above.
+            unsafe {
+                let _ = TranslateMessage(&message);
+                DispatchMessageW(&message);
+            }
+        } else {
+            thread::sleep(MESSAGE_LOOP_IDLE_SLEEP);
+        }
+    }
+
+    // SAFETY: `handler` was registered against this same
+    // `ui_automation_client` above and hasn't been removed yet.
+    if let Err(e) = unsafe { ui_automation_client.RemoveFocusChangedEventHandler(&handler) } {
+        log::warn!("Failed to remove UI Automation focus-changed handler: {e}");
+    }
+    drop(com_apartment_initialization_guard);
+}
+
+/// Start push-based focus tracking: a dedicated STA thread that keeps a
+/// `ComApartmentInitializationGuard` and `IUIAutomation` client alive for as
+/// long as the returned handle lives, with an
+/// `IUIAutomationFocusChangedEventHandler` registered against it.
+/// `on_snapshot_change` is invoked - from that thread - with a freshly-built
+/// `ActiveAppContextSnapshot` tagged `FocusEventSource::Uia` on every
+/// reported focus change, including focus moving between controls within
+/// the same window (e.g. into a browser's address bar) that a poll loop
+/// watching only the foreground window on a timer would miss between ticks.
+///
+/// Returns `None` if the dedicated thread fails to spawn or the handler
+/// fails to register; callers should fall back to polling in that case.
+pub fn start_event_driven_focus_watcher(
+    on_snapshot_change: impl Fn(ActiveAppContextSnapshot) + Send + Sync + 'static,
+) -> Option<EventDrivenFocusWatcherHandle> {
+    let should_stop = Arc::new(AtomicBool::new(false));
+    let should_stop_for_thread = should_stop.clone();
+    let (registration_result_sender, registration_result_receiver) = mpsc::sync_channel::<bool>(1);
+
+    let thread_handle = thread::Builder::new()
+        .name("focus-changed-event-watcher".to_string())
+        .spawn(move || {
+            run_focus_changed_event_loop(
+                should_stop_for_thread,
+                registration_result_sender,
+                on_snapshot_change,
+            );
+        })
+        .ok()?;
+
+    let registration_succeeded = registration_result_receiver
+        .recv_timeout(FOCUS_CHANGED_HANDLER_REGISTRATION_TIMEOUT)
+        .unwrap_or(false);
+    if !registration_succeeded {
+        should_stop.store(true, Ordering::SeqCst);
+        let _ = thread_handle.join();
+        return None;
+    }
+
+    Some(EventDrivenFocusWatcherHandle {
+        should_stop,
+        thread_handle: Some(thread_handle),
+    })
+}
+
 #[cfg(test)]
 #[path = "../tests/active_app_context_windows_tests.rs"]
 mod focus_windows_tests;
@@ -1,49 +1,126 @@
-use rodio::source::Source;
-use rodio::{Decoder, OutputStreamBuilder};
+//! Audio cue subsystem for recording start/stop/error feedback.
+//!
+//! Cues are decoded once into `Buffered` sources and played through a
+//! persistent `OutputStream`, so repeated plays don't re-decode the audio or
+//! re-open the output device the way a fresh `OutputStreamBuilder` per call
+//! would. Playback is fire-and-forget: a cue failure is logged and ignored,
+//! matching the crate's graceful-degradation style for audio elsewhere.
+
+use cpal::traits::{DeviceTrait, HostTrait};
+use rodio::source::{Amplify, Buffered, Source};
+use rodio::{Decoder, OutputStream, OutputStreamBuilder};
 use std::io::Cursor;
-use std::thread;
-use std::time::Duration;
 
-/// Types of sounds that can be played
+/// Types of cues that can be played.
 #[derive(Debug, Clone, Copy)]
 pub enum SoundType {
     RecordingStart,
     RecordingStop,
+    Error,
 }
 
 // Embed audio files at compile time
 const START_SOUND: &[u8] = include_bytes!("assets/start.mp3");
 const STOP_SOUND: &[u8] = include_bytes!("assets/stop.mp3");
+const ERROR_SOUND: &[u8] = include_bytes!("assets/error.mp3");
+
+type CueSource = Buffered<Amplify<Decoder<Cursor<&'static [u8]>>>>;
+
+fn decode_cue(
+    sound_data: &'static [u8],
+    sound_volume: f32,
+) -> Result<CueSource, rodio::decoder::DecoderError> {
+    let cursor = Cursor::new(sound_data);
+    Ok(Decoder::new(cursor)?.amplify(sound_volume).buffered())
+}
 
-/// Play a sound effect (non-blocking)
-pub fn play_sound(sound_type: SoundType) {
-    thread::spawn(move || {
-        if let Err(e) = play_sound_blocking(sound_type) {
-            log::warn!("Failed to play sound: {}", e);
+/// Open the output stream named `sound_output_device_id` via `cpal`, falling
+/// back to the system default if it's unset or no longer connected.
+fn open_output_stream(sound_output_device_id: Option<&str>) -> Result<OutputStream, String> {
+    if let Some(sound_output_device_id) = sound_output_device_id {
+        let matching_device =
+            cpal::default_host()
+                .output_devices()
+                .ok()
+                .and_then(|mut output_devices| {
+                    output_devices
+                        .find(|device| device.name().as_deref() == Ok(sound_output_device_id))
+                });
+        if let Some(matching_device) = matching_device {
+            match OutputStreamBuilder::from_device(matching_device)
+                .and_then(|builder| builder.open_stream())
+            {
+                Ok(stream) => return Ok(stream),
+                Err(e) => {
+                    log::warn!(
+                        "Failed to open sound output device '{sound_output_device_id}', \
+                         falling back to default: {e}"
+                    );
+                }
+            }
         }
-    });
+    }
+
+    OutputStreamBuilder::open_default_stream().map_err(|e| e.to_string())
 }
 
-fn play_sound_blocking(
-    sound_type: SoundType,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let stream = OutputStreamBuilder::open_default_stream()?;
+/// Plays recording start/stop/error cues through a persistent output stream.
+///
+/// Construct once (e.g. during app setup) and reuse for every cue; the
+/// decoded sources are cheap to re-play and the output device stays open
+/// for the lifetime of this value.
+pub struct AudioCuePlayer {
+    // Kept alive for as long as we want cues to play; dropping it closes
+    // the output device.
+    stream: OutputStream,
+    start_cue: CueSource,
+    stop_cue: CueSource,
+    error_cue: CueSource,
+}
 
-    let sound_data = match sound_type {
-        SoundType::RecordingStart => START_SOUND,
-        SoundType::RecordingStop => STOP_SOUND,
-    };
+impl AudioCuePlayer {
+    /// Open `sound_output_device_id` (or the default output device) and
+    /// decode all cues once, amplified to `sound_volume` (0.0-1.0).
+    ///
+    /// Returns `None` (logging a warning) if the output device can't be
+    /// opened or a cue fails to decode, so callers can degrade gracefully
+    /// instead of crashing the app over audio feedback.
+    pub fn new(sound_volume: f32, sound_output_device_id: Option<&str>) -> Option<Self> {
+        let stream = match open_output_stream(sound_output_device_id) {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::warn!("Audio cues not available, failed to open output stream: {e}");
+                return None;
+            }
+        };
 
-    let cursor = Cursor::new(sound_data);
-    let source = Decoder::new(cursor)?.amplify(0.3);
+        let start_cue = decode_cue(START_SOUND, sound_volume)
+            .inspect_err(|e| log::warn!("Failed to decode recording-start cue: {e}"))
+            .ok()?;
+        let stop_cue = decode_cue(STOP_SOUND, sound_volume)
+            .inspect_err(|e| log::warn!("Failed to decode recording-stop cue: {e}"))
+            .ok()?;
+        let error_cue = decode_cue(ERROR_SOUND, sound_volume)
+            .inspect_err(|e| log::warn!("Failed to decode error cue: {e}"))
+            .ok()?;
 
-    // Get duration for sleep, default to 500ms if unknown
-    let duration = source
-        .total_duration()
-        .unwrap_or(Duration::from_millis(500));
+        Some(Self {
+            stream,
+            start_cue,
+            stop_cue,
+            error_cue,
+        })
+    }
 
-    stream.mixer().add(source);
-    thread::sleep(duration + Duration::from_millis(50));
+    /// Play a cue (non-blocking). Never panics or propagates failures - a
+    /// cue not playing is not worth interrupting dictation over.
+    pub fn play(&self, sound_type: SoundType) {
+        let cue = match sound_type {
+            SoundType::RecordingStart => &self.start_cue,
+            SoundType::RecordingStop => &self.stop_cue,
+            SoundType::Error => &self.error_cue,
+        };
 
-    Ok(())
+        self.stream.mixer().add(cue.clone());
+    }
 }
@@ -0,0 +1,253 @@
+//! Real-time microphone capture: streams audio to the server as Opus frames
+//! while recording, instead of buffering the whole clip and uploading it
+//! once recording stops. This is the low-latency path; the existing
+//! record-then-upload flow stays available as a fallback (see
+//! `AppSettings::streaming_mode`).
+//!
+//! The `cpal` input callback only copies samples into a channel - all the
+//! actual work (resampling, 20ms framing, Opus encoding, upload) happens on
+//! a dedicated async task so the audio callback itself never blocks.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use audiopus::coder::Encoder;
+use audiopus::{Application, Channels, SampleRate};
+use cpal::traits::{DeviceTrait, StreamTrait};
+use cpal::{Device, SampleFormat, StreamConfig};
+use tauri_plugin_http::reqwest::Client;
+use tokio::sync::mpsc;
+
+/// Sample rate Opus frames are encoded at, regardless of the input device's
+/// native rate.
+const STREAM_SAMPLE_RATE_HZ: u32 = 16_000;
+/// Frame size in samples at `STREAM_SAMPLE_RATE_HZ` (20ms, as required by
+/// Opus VoIP mode).
+const FRAME_SAMPLES: usize = 320;
+/// Opus frames are never larger than this at the bitrates we use.
+const MAX_ENCODED_FRAME_BYTES: usize = 4000;
+
+/// Where to push encoded frames: the same server/pairing identity used by
+/// `ConfigSyncState`.
+pub struct StreamingUploadTarget {
+    pub client: Client,
+    pub server_url: String,
+    pub client_uuid: String,
+}
+
+/// Handle to a running streaming capture session.
+///
+/// Dropping it stops the input stream and signals the upload task to flush
+/// the final partial frame and send an end-of-stream marker, mirroring
+/// `FocusWatcherHandle`'s stop-on-drop pattern.
+pub struct StreamingCaptureHandle {
+    should_stop: Arc<AtomicBool>,
+    _stream: cpal::Stream,
+}
+
+impl StreamingCaptureHandle {
+    pub fn stop(&self) {
+        self.should_stop.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for StreamingCaptureHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Downmix an interleaved multi-channel buffer to mono by averaging channels.
+fn downmix_to_mono(samples: &[f32], channels: u16) -> Vec<f32> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+
+    let channels = channels as usize;
+    samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+/// Linearly resample mono `samples` from `input_rate` to `STREAM_SAMPLE_RATE_HZ`.
+fn resample_to_stream_rate(samples: &[f32], input_rate: u32) -> Vec<f32> {
+    if samples.is_empty() || input_rate == STREAM_SAMPLE_RATE_HZ {
+        return samples.to_vec();
+    }
+
+    let ratio = STREAM_SAMPLE_RATE_HZ as f64 / input_rate as f64;
+    let output_len = (samples.len() as f64 * ratio).round() as usize;
+    (0..output_len)
+        .map(|output_index| {
+            let source_position = output_index as f64 / ratio;
+            let left_index = source_position.floor() as usize;
+            let right_index = (left_index + 1).min(samples.len() - 1);
+            let fraction = (source_position - left_index as f64) as f32;
+            samples[left_index] * (1.0 - fraction) + samples[right_index] * fraction
+        })
+        .collect()
+}
+
+/// Open `device` and begin streaming Opus-encoded 20ms frames to
+/// `upload_target` over HTTP as they're produced, with a monotonically
+/// increasing sequence number per frame.
+pub fn start_streaming_capture(
+    device: Device,
+    upload_target: StreamingUploadTarget,
+) -> Result<StreamingCaptureHandle, String> {
+    let config = device
+        .default_input_config()
+        .map_err(|e| format!("Failed to get default input config: {e}"))?;
+    let input_sample_rate = config.sample_rate().0;
+    let input_channels = config.channels();
+    let sample_format = config.sample_format();
+    let stream_config: StreamConfig = config.into();
+
+    let (sample_tx, sample_rx) = mpsc::unbounded_channel::<Vec<f32>>();
+    let err_fn = |err| log::warn!("Streaming capture input stream error: {err}");
+
+    let stream = match sample_format {
+        SampleFormat::F32 => device.build_input_stream(
+            &stream_config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                let _ = sample_tx.send(data.to_vec());
+            },
+            err_fn,
+            None,
+        ),
+        SampleFormat::I16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                let samples = data.iter().map(|&s| s as f32 / i16::MAX as f32).collect();
+                let _ = sample_tx.send(samples);
+            },
+            err_fn,
+            None,
+        ),
+        other => {
+            return Err(format!(
+                "Unsupported sample format for streaming capture: {other:?}"
+            ))
+        }
+    }
+    .map_err(|e| format!("Failed to build input stream: {e}"))?;
+
+    stream
+        .play()
+        .map_err(|e| format!("Failed to start input stream: {e}"))?;
+
+    let should_stop = Arc::new(AtomicBool::new(false));
+    let worker_should_stop = should_stop.clone();
+    tauri::async_runtime::spawn(run_encode_and_upload_loop(
+        sample_rx,
+        input_sample_rate,
+        input_channels,
+        worker_should_stop,
+        upload_target,
+    ));
+
+    Ok(StreamingCaptureHandle {
+        should_stop,
+        _stream: stream,
+    })
+}
+
+async fn run_encode_and_upload_loop(
+    mut sample_rx: mpsc::UnboundedReceiver<Vec<f32>>,
+    input_sample_rate: u32,
+    input_channels: u16,
+    should_stop: Arc<AtomicBool>,
+    upload_target: StreamingUploadTarget,
+) {
+    let mut encoder = match Encoder::new(SampleRate::Hz16000, Channels::Mono, Application::Voip) {
+        Ok(encoder) => encoder,
+        Err(e) => {
+            log::warn!("Failed to create Opus encoder, streaming capture disabled: {e}");
+            return;
+        }
+    };
+
+    let mut pending_samples: Vec<f32> = Vec::new();
+    let mut sequence_number: u64 = 0;
+    let mut encode_buffer = [0u8; MAX_ENCODED_FRAME_BYTES];
+
+    while let Some(raw_samples) = sample_rx.recv().await {
+        let mono_samples = downmix_to_mono(&raw_samples, input_channels);
+        let resampled = resample_to_stream_rate(&mono_samples, input_sample_rate);
+        pending_samples.extend(resampled);
+
+        while pending_samples.len() >= FRAME_SAMPLES {
+            let frame: Vec<f32> = pending_samples.drain(..FRAME_SAMPLES).collect();
+            push_encoded_frame(
+                &mut encoder,
+                &frame,
+                &mut encode_buffer,
+                sequence_number,
+                &upload_target,
+            )
+            .await;
+            sequence_number += 1;
+        }
+
+        if should_stop.load(Ordering::SeqCst) {
+            break;
+        }
+    }
+
+    if !pending_samples.is_empty() {
+        pending_samples.resize(FRAME_SAMPLES, 0.0);
+        push_encoded_frame(
+            &mut encoder,
+            &pending_samples,
+            &mut encode_buffer,
+            sequence_number,
+            &upload_target,
+        )
+        .await;
+    }
+
+    send_end_of_stream_marker(&upload_target).await;
+}
+
+async fn push_encoded_frame(
+    encoder: &mut Encoder,
+    frame: &[f32],
+    encode_buffer: &mut [u8],
+    sequence_number: u64,
+    upload_target: &StreamingUploadTarget,
+) {
+    let encoded_len = match encoder.encode_float(frame, encode_buffer) {
+        Ok(encoded_len) => encoded_len,
+        Err(e) => {
+            log::warn!("Failed to encode streaming audio frame {sequence_number}: {e}");
+            return;
+        }
+    };
+
+    let endpoint_url = format!("{}/api/stream/frame", upload_target.server_url);
+    if let Err(e) = upload_target
+        .client
+        .post(&endpoint_url)
+        .header("X-Client-UUID", &upload_target.client_uuid)
+        .header("X-Sequence-Number", sequence_number.to_string())
+        .body(encode_buffer[..encoded_len].to_vec())
+        .send()
+        .await
+    {
+        log::warn!("Failed to push streaming audio frame {sequence_number}: {e}");
+    }
+}
+
+async fn send_end_of_stream_marker(upload_target: &StreamingUploadTarget) {
+    let endpoint_url = format!("{}/api/stream/end", upload_target.server_url);
+    if let Err(e) = upload_target
+        .client
+        .post(&endpoint_url)
+        .header("X-Client-UUID", &upload_target.client_uuid)
+        .send()
+        .await
+    {
+        log::warn!("Failed to send streaming end-of-stream marker: {e}");
+    }
+}
@@ -0,0 +1,105 @@
+//! Microphone enumeration and selection, backed by `cpal`'s default host.
+//!
+//! The device "id" we persist in settings is the device's `cpal` name rather
+//! than any OS-level stable identifier - `cpal` doesn't expose one
+//! consistently across platforms, and the name is good enough to survive a
+//! settings round-trip and to detect when a previously-selected mic has
+//! disappeared (e.g. an unplugged USB device).
+
+use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::Device;
+
+use crate::settings::AppSettings;
+
+/// One input device's capabilities, as reported by `cpal`, for a UI picker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputDeviceDescription {
+    /// Stable-enough identifier to persist in settings (the device name).
+    pub id: String,
+    pub display_name: String,
+    pub default_sample_rate_hz: Option<u32>,
+    pub default_channels: Option<u16>,
+    pub default_sample_format: Option<String>,
+    pub supported_configs: Vec<SupportedInputConfigDescription>,
+}
+
+/// One entry from `Device::supported_input_configs()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SupportedInputConfigDescription {
+    pub channels: u16,
+    pub min_sample_rate_hz: u32,
+    pub max_sample_rate_hz: u32,
+    pub sample_format: String,
+}
+
+fn describe_supported_input_configs(device: &Device) -> Vec<SupportedInputConfigDescription> {
+    let Ok(supported_configs) = device.supported_input_configs() else {
+        return Vec::new();
+    };
+
+    supported_configs
+        .map(|supported_config_range| SupportedInputConfigDescription {
+            channels: supported_config_range.channels(),
+            min_sample_rate_hz: supported_config_range.min_sample_rate().0,
+            max_sample_rate_hz: supported_config_range.max_sample_rate().0,
+            sample_format: supported_config_range.sample_format().to_string(),
+        })
+        .collect()
+}
+
+fn describe_input_device(device: Device) -> Option<InputDeviceDescription> {
+    let id = device.name().ok()?;
+    let default_input_config = device.default_input_config().ok();
+
+    Some(InputDeviceDescription {
+        display_name: id.clone(),
+        id,
+        default_sample_rate_hz: default_input_config.as_ref().map(|c| c.sample_rate().0),
+        default_channels: default_input_config.as_ref().map(|c| c.channels()),
+        default_sample_format: default_input_config
+            .as_ref()
+            .map(|c| c.sample_format().to_string()),
+        supported_configs: describe_supported_input_configs(&device),
+    })
+}
+
+/// List every input device the default host can see, with enough detail for
+/// a UI picker to show each device's capabilities.
+pub fn list_input_devices() -> Vec<InputDeviceDescription> {
+    let host = cpal::default_host();
+    let Ok(input_devices) = host.input_devices() else {
+        return Vec::new();
+    };
+
+    input_devices.filter_map(describe_input_device).collect()
+}
+
+/// Whether `device_id` (a persisted `selected_mic_id`) still refers to a
+/// connected input device.
+pub fn input_device_exists(device_id: &str) -> bool {
+    let host = cpal::default_host();
+    let Ok(mut input_devices) = host.input_devices() else {
+        return false;
+    };
+
+    input_devices.any(|device| device.name().as_deref() == Ok(device_id))
+}
+
+/// Resolve the `cpal::Device` the app should record from for `settings`:
+/// the device named by `selected_mic_id` if it's still connected, otherwise
+/// the host's default input device.
+pub fn resolve_input_device(settings: &AppSettings) -> Option<Device> {
+    let host = cpal::default_host();
+
+    if let Some(selected_mic_id) = settings.selected_mic_id.as_deref() {
+        if let Ok(mut input_devices) = host.input_devices() {
+            if let Some(selected_device) =
+                input_devices.find(|device| device.name().as_deref() == Ok(selected_mic_id))
+            {
+                return Some(selected_device);
+            }
+        }
+    }
+
+    host.default_input_device()
+}
@@ -0,0 +1,146 @@
+//! Linux audio mute control implementation using the ALSA mixer.
+//!
+//! Mirrors the pnmixer-rust `AlsaCard` approach: open the default card's
+//! `Mixer`, locate a playable `Selem` (preferring "Master"), and toggle its
+//! playback switch across all channels.
+
+use super::{AudioControlError, SystemAudioControl};
+use alsa::mixer::{Mixer, SelemChannelId, SelemId};
+
+const DEFAULT_CARD_NAME: &str = "default";
+const PREFERRED_SELEM_NAME: &str = "Master";
+
+/// Linux audio controller using the ALSA mixer.
+pub struct LinuxAudioController {
+    selem_name: String,
+}
+
+// SAFETY: `LinuxAudioController` only stores the selector used to re-open the
+// mixer on each call; ALSA handles are not cached across threads.
+unsafe impl Send for LinuxAudioController {}
+unsafe impl Sync for LinuxAudioController {}
+
+impl LinuxAudioController {
+    /// Create a new Linux audio controller.
+    ///
+    /// Opens the default ALSA card once to confirm a playable mixer element
+    /// exists, then remembers its name for subsequent operations.
+    pub fn new() -> Result<Self, AudioControlError> {
+        let mixer = Self::open_mixer()?;
+        let selem = Self::find_playable_selem(&mixer)?;
+        let selem_name = selem.get_id().get_name().unwrap_or_default().to_string();
+
+        Ok(Self { selem_name })
+    }
+
+    fn open_mixer() -> Result<Mixer, AudioControlError> {
+        Mixer::new(DEFAULT_CARD_NAME, false).map_err(|e| {
+            AudioControlError::InitializationFailed(format!(
+                "Failed to open ALSA mixer for {DEFAULT_CARD_NAME}: {e}"
+            ))
+        })
+    }
+
+    /// Find a playable `Selem`, preferring "Master" and falling back to the
+    /// first element that has a playback volume or a playback switch.
+    fn find_playable_selem(mixer: &Mixer) -> Result<alsa::mixer::Selem<'_>, AudioControlError> {
+        let mut fallback = None;
+
+        for selem in mixer.iter().filter_map(alsa::mixer::Selem::new) {
+            if !selem.has_playback_volume() && !selem.has_playback_switch() {
+                continue;
+            }
+
+            let name = selem.get_id().get_name().unwrap_or_default().to_string();
+            if name == PREFERRED_SELEM_NAME {
+                return Ok(selem);
+            }
+
+            if fallback.is_none() {
+                fallback = Some(selem);
+            }
+        }
+
+        fallback.ok_or_else(|| {
+            AudioControlError::InitializationFailed(
+                "No playable ALSA mixer element found".to_string(),
+            )
+        })
+    }
+
+    fn with_selem<T>(
+        &self,
+        f: impl FnOnce(&alsa::mixer::Selem<'_>) -> Result<T, AudioControlError>,
+    ) -> Result<T, AudioControlError> {
+        let mixer = Self::open_mixer()?;
+        let selem_id = SelemId::new(&self.selem_name, 0);
+        let selem = mixer.find_selem(&selem_id).ok_or_else(|| {
+            AudioControlError::InitializationFailed(format!(
+                "ALSA mixer element '{}' disappeared",
+                self.selem_name
+            ))
+        })?;
+
+        f(&selem)
+    }
+}
+
+impl SystemAudioControl for LinuxAudioController {
+    fn is_muted(&self) -> Result<bool, AudioControlError> {
+        self.with_selem(|selem| {
+            // A channel reporting playback switch == 0 means that channel is muted.
+            // We consider the element muted only if every channel agrees.
+            let mut any_channel_checked = false;
+            for channel in SelemChannelId::all() {
+                if let Ok(switch_value) = selem.get_playback_switch(*channel) {
+                    any_channel_checked = true;
+                    if switch_value != 0 {
+                        return Ok(false);
+                    }
+                }
+            }
+
+            if !any_channel_checked {
+                return Err(AudioControlError::GetPropertyFailed(
+                    "Mixer element has no readable playback switch channels".to_string(),
+                ));
+            }
+
+            Ok(true)
+        })
+    }
+
+    fn set_muted(&self, muted: bool) -> Result<(), AudioControlError> {
+        self.with_selem(|selem| {
+            selem
+                .set_playback_switch_all(i32::from(!muted))
+                .map_err(|e| AudioControlError::SetPropertyFailed(format!("{e}")))
+        })
+    }
+
+    fn get_volume(&self) -> Result<f32, AudioControlError> {
+        self.with_selem(|selem| {
+            let (min, max) = selem.get_playback_volume_range();
+            let raw = selem
+                .get_playback_volume(SelemChannelId::FrontLeft)
+                .map_err(|e| AudioControlError::GetPropertyFailed(format!("{e}")))?;
+
+            if max <= min {
+                return Ok(0.0);
+            }
+
+            Ok(((raw - min) as f32 / (max - min) as f32).clamp(0.0, 1.0))
+        })
+    }
+
+    fn set_volume(&self, level: f32) -> Result<(), AudioControlError> {
+        self.with_selem(|selem| {
+            let (min, max) = selem.get_playback_volume_range();
+            let raw = min + ((max - min) as f32 * level.clamp(0.0, 1.0)).round() as i64;
+
+            selem
+                .set_playback_volume_all(raw)
+                .map_err(|e| AudioControlError::SetPropertyFailed(format!("{e}")))
+        })
+    }
+}
@@ -5,17 +5,31 @@
 
 use super::{AudioControlError, SystemAudioControl};
 use objc2_core_audio::{
-    kAudioDevicePropertyMute, kAudioDevicePropertyScopeOutput,
+    kAudioDevicePropertyDeviceIsRunningSomewhere, kAudioDevicePropertyMute,
+    kAudioDevicePropertyScopeOutput, kAudioDevicePropertyVolumeScalar,
     kAudioHardwarePropertyDefaultOutputDevice, kAudioObjectPropertyElementMain,
-    kAudioObjectPropertyScopeGlobal, kAudioObjectSystemObject, AudioObjectGetPropertyData,
-    AudioObjectPropertyAddress, AudioObjectSetPropertyData,
+    kAudioObjectPropertyScopeGlobal, kAudioObjectSystemObject, AudioObjectAddPropertyListener,
+    AudioObjectGetPropertyData, AudioObjectID, AudioObjectPropertyAddress,
+    AudioObjectSetPropertyData,
 };
 use std::ffi::c_void;
 use std::ptr::NonNull;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Client data handed to the `AudioObjectAddPropertyListener` callback
+/// registered on `kAudioHardwarePropertyDefaultOutputDevice`, so it can
+/// refresh the controller's cached device id and notify the app-level
+/// listener without needing a `&MacOSAudioController`.
+struct DeviceChangeContext {
+    device_id: Arc<AtomicU32>,
+    on_device_change: Mutex<Option<Box<dyn Fn() + Send + Sync>>>,
+}
 
 /// macOS audio controller using `CoreAudio`.
 pub struct MacOSAudioController {
-    device_id: u32,
+    device_id: Arc<AtomicU32>,
+    device_change_context: Arc<DeviceChangeContext>,
 }
 
 // SAFETY: CoreAudio APIs are thread-safe
@@ -25,10 +39,80 @@ unsafe impl Sync for MacOSAudioController {}
 impl MacOSAudioController {
     /// Create a new macOS audio controller.
     ///
-    /// Gets the default output device ID for subsequent operations.
+    /// Gets the default output device ID for subsequent operations, and
+    /// registers a property listener so it stays current if the user
+    /// switches output devices mid-session.
     pub fn new() -> Result<Self, AudioControlError> {
-        let device_id = Self::get_default_output_device()?;
-        Ok(Self { device_id })
+        let device_id = Arc::new(AtomicU32::new(Self::get_default_output_device()?));
+        let device_change_context = Arc::new(DeviceChangeContext {
+            device_id: device_id.clone(),
+            on_device_change: Mutex::new(None),
+        });
+
+        let address = AudioObjectPropertyAddress {
+            mSelector: kAudioHardwarePropertyDefaultOutputDevice,
+            mScope: kAudioObjectPropertyScopeGlobal,
+            mElement: kAudioObjectPropertyElementMain,
+        };
+        let status = unsafe {
+            AudioObjectAddPropertyListener(
+                kAudioObjectSystemObject as u32,
+                NonNull::new((&raw const address).cast_mut()).unwrap(),
+                Some(Self::handle_default_output_device_changed),
+                Arc::as_ptr(&device_change_context)
+                    .cast_mut()
+                    .cast::<c_void>(),
+            )
+        };
+        if status != 0 {
+            log::warn!(
+                "Failed to register default output device listener (OSStatus: {status}), \
+                 output device changes mid-recording won't be tracked"
+            );
+        }
+
+        Ok(Self {
+            device_id,
+            device_change_context,
+        })
+    }
+
+    /// `AudioObjectPropertyListenerProc` fired when the default output
+    /// device changes. Re-queries the new device id and, if the old device
+    /// was muted, transfers that mute to the new device and unmutes the old
+    /// one so it isn't left orphaned muted.
+    extern "C" fn handle_default_output_device_changed(
+        _object_id: AudioObjectID,
+        _num_addresses: u32,
+        _addresses: *const AudioObjectPropertyAddress,
+        client_data: *mut c_void,
+    ) -> i32 {
+        // SAFETY: `client_data` is the `Arc<DeviceChangeContext>` registered
+        // in `new()`, which outlives this listener for the controller's
+        // entire lifetime.
+        let context = unsafe { &*client_data.cast::<DeviceChangeContext>() };
+
+        let old_device_id = context.device_id.load(Ordering::SeqCst);
+        let Ok(new_device_id) = Self::get_default_output_device() else {
+            return 0;
+        };
+        if new_device_id == old_device_id {
+            return 0;
+        }
+
+        if let Ok(true) = Self::get_u32_property_for_device(old_device_id, kAudioDevicePropertyMute)
+            .map(|muted| muted != 0)
+        {
+            let _ = Self::set_u32_property_for_device(new_device_id, kAudioDevicePropertyMute, 1);
+            let _ = Self::set_u32_property_for_device(old_device_id, kAudioDevicePropertyMute, 0);
+        }
+
+        context.device_id.store(new_device_id, Ordering::SeqCst);
+        if let Some(on_device_change) = context.on_device_change.lock().unwrap().as_ref() {
+            on_device_change();
+        }
+
+        0
     }
 
     /// Get the default audio output device ID.
@@ -68,8 +152,11 @@ impl MacOSAudioController {
         Ok(device_id)
     }
 
-    /// Get a u32 property from the default output device.
-    fn get_u32_property(&self, selector: u32) -> Result<u32, AudioControlError> {
+    /// Get a u32 property from `device_id`.
+    fn get_u32_property_for_device(
+        device_id: u32,
+        selector: u32,
+    ) -> Result<u32, AudioControlError> {
         let address = AudioObjectPropertyAddress {
             mSelector: selector,
             mScope: kAudioDevicePropertyScopeOutput,
@@ -81,7 +168,7 @@ impl MacOSAudioController {
 
         let status = unsafe {
             AudioObjectGetPropertyData(
-                self.device_id,
+                device_id,
                 NonNull::new((&raw const address).cast_mut()).unwrap(),
                 0,
                 std::ptr::null(),
@@ -99,19 +186,94 @@ impl MacOSAudioController {
         Ok(value)
     }
 
+    /// Get a u32 property from the default output device.
+    fn get_u32_property(&self, selector: u32) -> Result<u32, AudioControlError> {
+        Self::get_u32_property_for_device(self.device_id.load(Ordering::SeqCst), selector)
+    }
+
+    /// Set a u32 property on `device_id`.
+    fn set_u32_property_for_device(
+        device_id: u32,
+        selector: u32,
+        value: u32,
+    ) -> Result<(), AudioControlError> {
+        let address = AudioObjectPropertyAddress {
+            mSelector: selector,
+            mScope: kAudioDevicePropertyScopeOutput,
+            mElement: kAudioObjectPropertyElementMain,
+        };
+
+        let size = 4u32;
+
+        let status = unsafe {
+            AudioObjectSetPropertyData(
+                device_id,
+                NonNull::new((&raw const address).cast_mut()).unwrap(),
+                0,
+                std::ptr::null(),
+                size,
+                NonNull::new((&raw const value).cast_mut().cast::<c_void>()).unwrap(),
+            )
+        };
+
+        if status != 0 {
+            return Err(AudioControlError::SetPropertyFailed(format!(
+                "OSStatus: {status}"
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Set a u32 property on the default output device.
     fn set_u32_property(&self, selector: u32, value: u32) -> Result<(), AudioControlError> {
+        Self::set_u32_property_for_device(self.device_id.load(Ordering::SeqCst), selector, value)
+    }
+
+    /// Get an f32 property from the default output device.
+    fn get_f32_property(&self, selector: u32) -> Result<f32, AudioControlError> {
         let address = AudioObjectPropertyAddress {
             mSelector: selector,
             mScope: kAudioDevicePropertyScopeOutput,
             mElement: kAudioObjectPropertyElementMain,
         };
 
-        let size = 4u32;
+        let mut value: f32 = 0.0;
+        let mut size = std::mem::size_of::<f32>() as u32;
+
+        let status = unsafe {
+            AudioObjectGetPropertyData(
+                self.device_id.load(Ordering::SeqCst),
+                NonNull::new((&raw const address).cast_mut()).unwrap(),
+                0,
+                std::ptr::null(),
+                NonNull::new(&raw mut size).unwrap(),
+                NonNull::new((&raw mut value).cast::<c_void>()).unwrap(),
+            )
+        };
+
+        if status != 0 {
+            return Err(AudioControlError::GetPropertyFailed(format!(
+                "OSStatus: {status}"
+            )));
+        }
+
+        Ok(value)
+    }
+
+    /// Set an f32 property on the default output device.
+    fn set_f32_property(&self, selector: u32, value: f32) -> Result<(), AudioControlError> {
+        let address = AudioObjectPropertyAddress {
+            mSelector: selector,
+            mScope: kAudioDevicePropertyScopeOutput,
+            mElement: kAudioObjectPropertyElementMain,
+        };
+
+        let size = std::mem::size_of::<f32>() as u32;
 
         let status = unsafe {
             AudioObjectSetPropertyData(
-                self.device_id,
+                self.device_id.load(Ordering::SeqCst),
                 NonNull::new((&raw const address).cast_mut()).unwrap(),
                 0,
                 std::ptr::null(),
@@ -139,4 +301,47 @@ impl SystemAudioControl for MacOSAudioController {
     fn set_muted(&self, muted: bool) -> Result<(), AudioControlError> {
         self.set_u32_property(kAudioDevicePropertyMute, u32::from(muted))
     }
+
+    fn get_volume(&self) -> Result<f32, AudioControlError> {
+        self.get_f32_property(kAudioDevicePropertyVolumeScalar)
+            .map(|v| v.clamp(0.0, 1.0))
+    }
+
+    fn set_volume(&self, level: f32) -> Result<(), AudioControlError> {
+        self.set_f32_property(kAudioDevicePropertyVolumeScalar, level.clamp(0.0, 1.0))
+    }
+
+    fn register_device_change_listener(&self, on_device_change: Box<dyn Fn() + Send + Sync>) {
+        *self.device_change_context.on_device_change.lock().unwrap() = Some(on_device_change);
+    }
+
+    fn is_device_active(&self) -> Result<bool, AudioControlError> {
+        let address = AudioObjectPropertyAddress {
+            mSelector: kAudioDevicePropertyDeviceIsRunningSomewhere,
+            mScope: kAudioObjectPropertyScopeGlobal,
+            mElement: kAudioObjectPropertyElementMain,
+        };
+
+        let mut value: u32 = 0;
+        let mut size = 4u32;
+
+        let status = unsafe {
+            AudioObjectGetPropertyData(
+                self.device_id.load(Ordering::SeqCst),
+                NonNull::new((&raw const address).cast_mut()).unwrap(),
+                0,
+                std::ptr::null(),
+                NonNull::new(&raw mut size).unwrap(),
+                NonNull::new((&raw mut value).cast::<c_void>()).unwrap(),
+            )
+        };
+
+        if status != 0 {
+            return Err(AudioControlError::GetPropertyFailed(format!(
+                "OSStatus: {status}"
+            )));
+        }
+
+        Ok(value != 0)
+    }
 }
@@ -4,14 +4,16 @@
 //! making it easy to swap implementations or migrate to a cross-platform library.
 
 use std::fmt;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 mod shared;
 
 // Platform-specific implementations
+#[cfg(target_os = "linux")]
+mod linux;
 #[cfg(target_os = "macos")]
 mod macos;
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
 mod stub;
 #[cfg(target_os = "windows")]
 mod windows;
@@ -43,6 +45,45 @@ impl fmt::Display for AudioControlError {
 
 impl std::error::Error for AudioControlError {}
 
+/// Which audio sessions a scoped mute/unmute operation should apply to,
+/// mirroring the Windows "app volume and device preferences" split between
+/// an app's regular playback and its default communications device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MuteScope {
+    /// Every session on the output endpoint (today's default behavior).
+    #[default]
+    All,
+    /// Only sessions that aren't classified as communications apps - e.g.
+    /// music, video, and browser media playback.
+    MediaContent,
+    /// Only sessions classified as communications apps (voice/video calling
+    /// software), leaving everything else audible.
+    CommunicationsOnly,
+}
+
+/// A single per-application audio session on the default output endpoint, as
+/// reported by `SystemAudioControl::enumerate_sessions`, for surfacing
+/// per-application mute state/control instead of an all-or-nothing global
+/// mute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioSession {
+    pub pid: u32,
+    pub name: String,
+    pub muted: bool,
+}
+
+/// An event reported about a per-process audio session subscribed via
+/// `SystemAudioControl::register_session_event_listener`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionReconciliationEvent {
+    /// The session's mute flag changed to `new_mute` for a reason other than
+    /// our own `set_muted_for_process` call.
+    MuteChanged(bool),
+    /// The session went away (its process exited or stopped rendering
+    /// audio).
+    Disconnected,
+}
+
 /// Trait for controlling system audio mute state.
 ///
 /// This minimal interface allows easy migration to a cross-platform library
@@ -53,15 +94,146 @@ pub trait SystemAudioControl: Send + Sync {
 
     /// Set system mute state
     fn set_muted(&self, muted: bool) -> Result<(), AudioControlError>;
+
+    /// Get the current output volume as a 0.0-1.0 scalar.
+    fn get_volume(&self) -> Result<f32, AudioControlError>;
+
+    /// Set the output volume from a 0.0-1.0 scalar.
+    fn set_volume(&self, level: f32) -> Result<(), AudioControlError>;
+
+    /// Whether audio is actively flowing through the output device right
+    /// now, as opposed to the device merely being available but silent.
+    /// Used to skip muting/ducking entirely when there's nothing playing to
+    /// attenuate.
+    ///
+    /// Backends that can't distinguish "running" from "idle" default to
+    /// reporting `true`, the conservative choice of always attenuating;
+    /// only macOS and Windows currently implement a real check.
+    fn is_device_active(&self) -> Result<bool, AudioControlError> {
+        Ok(true)
+    }
+
+    /// Check if the audio session belonging to process `pid` is muted.
+    ///
+    /// Backends that can't isolate per-process sessions return
+    /// `NotSupported`; only Windows currently implements this.
+    fn is_muted_for_process(&self, _pid: u32) -> Result<bool, AudioControlError> {
+        Err(AudioControlError::NotSupported)
+    }
+
+    /// Mute/unmute only the audio session belonging to `pid`, instead of the
+    /// whole output endpoint. Used to silence just the currently-focused
+    /// application during recording rather than every app's audio.
+    ///
+    /// Backends that can't isolate per-process sessions return
+    /// `NotSupported`; only Windows currently implements this.
+    fn set_muted_for_process(&self, _pid: u32, _muted: bool) -> Result<(), AudioControlError> {
+        Err(AudioControlError::NotSupported)
+    }
+
+    /// Check if every session matching `scope` is currently muted.
+    ///
+    /// Backends that can't classify sessions by scope return
+    /// `NotSupported` for anything other than `MuteScope::All`, which they
+    /// should treat the same as `is_muted`; only Windows currently
+    /// implements the finer-grained scopes.
+    fn is_muted_for_scope(&self, scope: MuteScope) -> Result<bool, AudioControlError> {
+        match scope {
+            MuteScope::All => self.is_muted(),
+            MuteScope::MediaContent | MuteScope::CommunicationsOnly => {
+                Err(AudioControlError::NotSupported)
+            }
+        }
+    }
+
+    /// Mute/unmute every session matching `scope`, instead of the whole
+    /// output endpoint. Used to duck media/content playback while leaving
+    /// notification or communications streams audible, or vice versa.
+    ///
+    /// Backends that can't classify sessions by scope return
+    /// `NotSupported` for anything other than `MuteScope::All`, which they
+    /// should treat the same as `set_muted`; only Windows currently
+    /// implements the finer-grained scopes.
+    fn set_muted_for_scope(&self, scope: MuteScope, muted: bool) -> Result<(), AudioControlError> {
+        match scope {
+            MuteScope::All => self.set_muted(muted),
+            MuteScope::MediaContent | MuteScope::CommunicationsOnly => {
+                Err(AudioControlError::NotSupported)
+            }
+        }
+    }
+
+    /// List every audio session on the output endpoint (whether or not it
+    /// is currently playing), so
+    /// callers can offer per-application muting/selection rather than an
+    /// all-or-nothing global mute - mirroring the per-element audio-channel
+    /// model where each source has its own independent mute/volume handle.
+    ///
+    /// Backends that can't enumerate sessions return `NotSupported`; only
+    /// Windows currently implements this.
+    fn enumerate_sessions(&self) -> Result<Vec<AudioSession>, AudioControlError> {
+        Err(AudioControlError::NotSupported)
+    }
+
+    /// Mute/unmute every session except the one belonging to `except_pid`,
+    /// instead of a single process or the whole endpoint. Used to duck
+    /// everything except the app Tambourine is currently typing into.
+    ///
+    /// Built entirely out of `enumerate_sessions` and
+    /// `set_muted_for_process`, so any backend that implements those two
+    /// gets this for free; backends that implement neither inherit the
+    /// `NotSupported` error from `enumerate_sessions`.
+    fn set_muted_for_all_except_process(
+        &self,
+        except_pid: u32,
+        muted: bool,
+    ) -> Result<(), AudioControlError> {
+        for session in self.enumerate_sessions()? {
+            if session.pid != except_pid {
+                self.set_muted_for_process(session.pid, muted)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Subscribe to mute/disconnect events on the audio session belonging to
+    /// `pid`, so a per-process mute we hold can be reconciled if something
+    /// else (the user, or the app itself) changes it or the session goes
+    /// away. Backends that can't subscribe to per-session events may leave
+    /// this as a no-op; only Windows currently implements it.
+    fn register_session_event_listener(
+        &self,
+        _pid: u32,
+        _on_event: Box<dyn Fn(SessionReconciliationEvent) + Send + Sync>,
+    ) {
+    }
+
+    /// Register a listener that fires when mute/volume changes for a reason
+    /// other than our own `set_muted`/`set_volume` calls (e.g. the user
+    /// toggling mute from their OS volume mixer mid-recording).
+    ///
+    /// Backends that cannot distinguish their own writes from external ones
+    /// may leave this as a no-op; only Windows currently implements it.
+    fn register_external_change_listener(&self, _on_external_change: Box<dyn Fn() + Send + Sync>) {}
+
+    /// Register a listener that fires when the operating system's default
+    /// output device changes (e.g. headphones plugged in or unplugged),
+    /// after the backend has already refreshed its own cached device handle
+    /// and transferred any active mute to the new device. This is purely an
+    /// observability hook for callers that want to log or react to the
+    /// switch; backends that don't cache a device handle, or that re-resolve
+    /// the device on every call, may leave this as a no-op. Only macOS
+    /// currently implements it.
+    fn register_device_change_listener(&self, _on_device_change: Box<dyn Fn() + Send + Sync>) {}
 }
 
 /// Check if audio mute is supported on this platform.
 pub fn is_supported() -> bool {
-    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
     {
         true
     }
-    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
         false
     }
@@ -69,8 +241,11 @@ pub fn is_supported() -> bool {
 
 /// Create a platform-appropriate audio controller.
 ///
-/// Returns a boxed trait object that can control system audio.
-/// On unsupported platforms, returns a stub that does nothing.
+/// Returns a boxed trait object that can control system audio: the Windows
+/// WASAPI endpoint controller, the macOS CoreAudio controller, or the Linux
+/// ALSA mixer controller, selected at compile time and handed to
+/// `AudioMuteManager::from_controller` at runtime. On unsupported platforms,
+/// returns a stub that does nothing.
 pub fn create_controller() -> Result<Box<dyn SystemAudioControl>, AudioControlError> {
     #[cfg(target_os = "windows")]
     {
@@ -82,24 +257,56 @@ pub fn create_controller() -> Result<Box<dyn SystemAudioControl>, AudioControlEr
         macos::MacOSAudioController::new().map(|c| Box::new(c) as Box<dyn SystemAudioControl>)
     }
 
-    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    #[cfg(target_os = "linux")]
+    {
+        linux::LinuxAudioController::new().map(|c| Box::new(c) as Box<dyn SystemAudioControl>)
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
         Ok(Box::new(stub::StubAudioController::new()))
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum MuteState {
     #[default]
     NotMuting,
     MutedByUs,
     AudioWasAlreadyMutedByUser,
+    /// We lowered the volume instead of muting, and will restore this exact
+    /// level on unmute unless the user changes it first.
+    DuckedByUs {
+        previous_level: f32,
+    },
+    /// We muted only the focused application's audio session (via
+    /// `set_muted_for_process`) rather than the whole endpoint; `pid` is
+    /// remembered so `unmute()`/`Drop` restore exactly that session.
+    MutedProcessByUs {
+        pid: u32,
+    },
+    /// We muted only the sessions matching `scope` (via
+    /// `set_muted_for_scope`) rather than the whole endpoint or a single
+    /// process; `scope` is remembered so `unmute()`/`Drop` restore exactly
+    /// those sessions.
+    MutedByScope {
+        scope: MuteScope,
+    },
+    /// We muted every session except the one belonging to `except_pid` (via
+    /// `set_muted_for_all_except_process`), so the app the user is dictating
+    /// into stays audible while everything else is ducked.
+    MutedAllExceptProcess {
+        except_pid: u32,
+    },
+    /// The user changed mute/volume themselves while we were muting/ducking;
+    /// we back off and leave their choice alone until the next recording.
+    UserOverrode,
 }
 
 /// Manages muting/unmuting system audio during recording.
 pub struct AudioMuteManager {
     controller: Box<dyn SystemAudioControl>,
-    state: Mutex<MuteState>,
+    state: Arc<Mutex<MuteState>>,
 }
 
 impl AudioMuteManager {
@@ -114,10 +321,22 @@ impl AudioMuteManager {
     }
 
     pub fn from_controller(controller: Box<dyn SystemAudioControl>) -> Self {
-        Self {
-            controller,
-            state: Mutex::new(MuteState::NotMuting),
-        }
+        let state = Arc::new(Mutex::new(MuteState::NotMuting));
+
+        let listener_state = state.clone();
+        controller.register_external_change_listener(Box::new(move || {
+            let mut state = listener_state.lock().unwrap();
+            if matches!(*state, MuteState::MutedByUs | MuteState::DuckedByUs { .. }) {
+                log::info!("User changed system audio mute/volume while recording, backing off");
+                *state = MuteState::UserOverrode;
+            }
+        }));
+
+        controller.register_device_change_listener(Box::new(|| {
+            log::info!("Default output device changed, audio mute target refreshed");
+        }));
+
+        Self { controller, state }
     }
 
     fn apply_mute_transition_decision(
@@ -125,15 +344,33 @@ impl AudioMuteManager {
         state: &mut MuteState,
         transition_decision: shared::MuteTransitionDecision,
     ) -> Result<(), AudioControlError> {
-        if let shared::MuteTransitionAction::SetMuted(next_mute_value) = transition_decision.action
-        {
-            self.controller.set_muted(next_mute_value)?;
+        match transition_decision.action {
+            shared::MuteTransitionAction::SetMuted(next_mute_value) => {
+                self.controller.set_muted(next_mute_value)?;
+            }
+            shared::MuteTransitionAction::SetMutedForProcess { pid, muted } => {
+                self.controller.set_muted_for_process(pid, muted)?;
+            }
+            shared::MuteTransitionAction::SetMutedForScope { scope, muted } => {
+                self.controller.set_muted_for_scope(scope, muted)?;
+            }
+            shared::MuteTransitionAction::SetMutedForAllExceptProcess { except_pid, muted } => {
+                self.controller
+                    .set_muted_for_all_except_process(except_pid, muted)?;
+            }
+            shared::MuteTransitionAction::SetVolume(next_volume) => {
+                self.controller.set_volume(next_volume)?;
+            }
+            shared::MuteTransitionAction::NoOp => {}
         }
 
         *state = transition_decision.next_state;
         Ok(())
     }
 
+    /// Mute system audio for recording, first snapshotting whether the
+    /// device is already muted so `unmute()` restores that prior state
+    /// exactly instead of blindly unmuting - see `MuteState::AudioWasAlreadyMutedByUser`.
     pub fn mute(&self) -> Result<(), AudioControlError> {
         let mut state = self.state.lock().unwrap();
 
@@ -143,16 +380,214 @@ impl AudioMuteManager {
 
         let audio_is_already_muted = self.controller.is_muted().unwrap_or(false);
         let transition_decision = shared::decide_mute_transition(*state, audio_is_already_muted);
+        let next_state = transition_decision.next_state;
         self.apply_mute_transition_decision(&mut state, transition_decision)?;
 
-        match transition_decision.next_state {
+        match next_state {
             MuteState::AudioWasAlreadyMutedByUser => {
                 log::info!("System audio already muted, skipping");
             }
             MuteState::MutedByUs => {
                 log::info!("System audio muted for recording");
             }
-            MuteState::NotMuting => {}
+            MuteState::NotMuting
+            | MuteState::MutedProcessByUs { .. }
+            | MuteState::MutedByScope { .. }
+            | MuteState::MutedAllExceptProcess { .. }
+            | MuteState::DuckedByUs { .. }
+            | MuteState::UserOverrode => {}
+        }
+
+        Ok(())
+    }
+
+    /// Mute only the audio session belonging to `pid` (the currently-focused
+    /// application), rather than the whole output endpoint. Falls back to
+    /// `AudioControlError::NotSupported` on backends that can't isolate
+    /// per-process sessions.
+    pub fn mute_process(&self, pid: u32) -> Result<(), AudioControlError> {
+        let mut state = self.state.lock().unwrap();
+
+        if *state != MuteState::NotMuting {
+            return Ok(());
+        }
+
+        let audio_is_already_muted = self.controller.is_muted_for_process(pid).unwrap_or(false);
+        let transition_decision =
+            shared::decide_mute_process_transition(*state, pid, audio_is_already_muted);
+        let next_state = transition_decision.next_state;
+        self.apply_mute_transition_decision(&mut state, transition_decision)?;
+
+        match next_state {
+            MuteState::AudioWasAlreadyMutedByUser => {
+                log::info!("Audio session for process {pid} already muted, skipping");
+            }
+            MuteState::MutedProcessByUs { pid } => {
+                log::info!("Audio session for process {pid} muted for recording");
+            }
+            MuteState::NotMuting
+            | MuteState::MutedByUs
+            | MuteState::MutedByScope { .. }
+            | MuteState::MutedAllExceptProcess { .. }
+            | MuteState::DuckedByUs { .. }
+            | MuteState::UserOverrode => {}
+        }
+
+        if let MuteState::MutedProcessByUs { pid } = next_state {
+            drop(state);
+            self.register_process_session_reconciliation(pid);
+        }
+
+        Ok(())
+    }
+
+    /// Mute only the sessions matching `scope` (e.g. media content vs.
+    /// communications apps), instead of the whole output endpoint. Falls
+    /// back to `AudioControlError::NotSupported` on backends that can't
+    /// classify sessions by scope.
+    pub fn mute_scope(&self, scope: MuteScope) -> Result<(), AudioControlError> {
+        let mut state = self.state.lock().unwrap();
+
+        if *state != MuteState::NotMuting {
+            return Ok(());
+        }
+
+        let audio_is_already_muted = self.controller.is_muted_for_scope(scope).unwrap_or(false);
+        let transition_decision =
+            shared::decide_mute_scope_transition(*state, scope, audio_is_already_muted);
+        let next_state = transition_decision.next_state;
+        self.apply_mute_transition_decision(&mut state, transition_decision)?;
+
+        match next_state {
+            MuteState::AudioWasAlreadyMutedByUser => {
+                log::info!("Audio for scope {scope:?} already muted, skipping");
+            }
+            MuteState::MutedByScope { scope } => {
+                log::info!("Audio for scope {scope:?} muted for recording");
+            }
+            MuteState::NotMuting
+            | MuteState::MutedByUs
+            | MuteState::MutedProcessByUs { .. }
+            | MuteState::MutedAllExceptProcess { .. }
+            | MuteState::DuckedByUs { .. }
+            | MuteState::UserOverrode => {}
+        }
+
+        Ok(())
+    }
+
+    /// Mute every audio session except the one belonging to `except_pid`,
+    /// instead of a single process, a scope, or the whole endpoint. Used to
+    /// duck everything except the app Tambourine is currently typing into.
+    /// Falls back to `AudioControlError::NotSupported` on backends that
+    /// can't enumerate sessions.
+    pub fn mute_all_except_process(&self, except_pid: u32) -> Result<(), AudioControlError> {
+        let mut state = self.state.lock().unwrap();
+
+        if *state != MuteState::NotMuting {
+            return Ok(());
+        }
+
+        let transition_decision =
+            shared::decide_mute_all_except_process_transition(*state, except_pid);
+        let next_state = transition_decision.next_state;
+        self.apply_mute_transition_decision(&mut state, transition_decision)?;
+
+        if let MuteState::MutedAllExceptProcess { except_pid } = next_state {
+            log::info!("All audio sessions except process {except_pid} muted for recording");
+        }
+
+        Ok(())
+    }
+
+    /// List every audio session on the output endpoint, whether or not it
+    /// is currently playing.
+    /// Falls back to `AudioControlError::NotSupported` on backends that
+    /// can't enumerate sessions.
+    pub fn enumerate_sessions(&self) -> Result<Vec<AudioSession>, AudioControlError> {
+        self.controller.enumerate_sessions()
+    }
+
+    /// Whether audio is actively flowing through the output device right
+    /// now. Callers can use this to skip muting/ducking (and any pre-roll
+    /// delay before it) when there's nothing playing to attenuate.
+    pub fn is_device_active(&self) -> Result<bool, AudioControlError> {
+        self.controller.is_device_active()
+    }
+
+    /// Mute every audio session except the currently-focused application's
+    /// (Windows only), reusing the same pid resolution as
+    /// `mute_focused_process`. No-op if the foreground window's process
+    /// can't be resolved.
+    #[cfg(target_os = "windows")]
+    pub fn mute_all_except_focused_process(&self) -> Result<(), AudioControlError> {
+        match crate::active_app_context::foreground_window_process_id() {
+            Some(pid) => self.mute_all_except_process(pid),
+            None => Ok(()),
+        }
+    }
+
+    /// Subscribe to mute/disconnect events on the audio session belonging to
+    /// `pid`, reconciling our `MuteState::MutedProcessByUs { pid }` if
+    /// something else changes that session's mute flag or it disconnects.
+    fn register_process_session_reconciliation(&self, pid: u32) {
+        let listener_state = self.state.clone();
+        self.controller.register_session_event_listener(
+            pid,
+            Box::new(move |event| {
+                let mut state = listener_state.lock().unwrap();
+                if *state != (MuteState::MutedProcessByUs { pid }) {
+                    return;
+                }
+
+                match event {
+                    SessionReconciliationEvent::MuteChanged(_) => {
+                        log::info!(
+                            "Audio session for process {pid} mute changed externally while we were managing it, backing off"
+                        );
+                        *state = MuteState::AudioWasAlreadyMutedByUser;
+                    }
+                    SessionReconciliationEvent::Disconnected => {
+                        log::info!(
+                            "Audio session for process {pid} disconnected, resetting mute state"
+                        );
+                        *state = MuteState::NotMuting;
+                    }
+                }
+            }),
+        );
+    }
+
+    /// Mute only the currently-focused application's audio session (Windows
+    /// only), reusing the process id the focus-tracking `active_app_context`
+    /// path already resolves via `GetWindowThreadProcessId`. No-op if the
+    /// foreground window's process can't be resolved.
+    #[cfg(target_os = "windows")]
+    pub fn mute_focused_process(&self) -> Result<(), AudioControlError> {
+        match crate::active_app_context::foreground_window_process_id() {
+            Some(pid) => self.mute_process(pid),
+            None => Ok(()),
+        }
+    }
+
+    /// Lower the output volume to `duck_level` (0.0-1.0) instead of a hard
+    /// mute, remembering the current level so it can be restored exactly on
+    /// unmute. No-op if we're already muting/ducking in some form.
+    pub fn duck(&self, duck_level: f32) -> Result<(), AudioControlError> {
+        let mut state = self.state.lock().unwrap();
+
+        if *state != MuteState::NotMuting {
+            return Ok(());
+        }
+
+        let current_volume = self.controller.get_volume()?;
+        let transition_decision =
+            shared::decide_duck_transition(*state, current_volume, duck_level);
+        let next_state = transition_decision.next_state;
+        self.apply_mute_transition_decision(&mut state, transition_decision)?;
+
+        if let MuteState::DuckedByUs { previous_level } = next_state {
+            log::info!("System audio ducked to {duck_level} (was {previous_level}) for recording");
         }
 
         Ok(())
@@ -168,9 +603,26 @@ impl AudioMuteManager {
             MuteState::MutedByUs => {
                 log::info!("System audio unmuted after recording");
             }
+            MuteState::DuckedByUs { previous_level } => {
+                log::info!("System audio volume restored to {previous_level} after recording");
+            }
+            MuteState::MutedProcessByUs { pid } => {
+                log::info!("Audio session for process {pid} unmuted after recording");
+            }
+            MuteState::MutedByScope { scope } => {
+                log::info!("Audio for scope {scope:?} unmuted after recording");
+            }
+            MuteState::MutedAllExceptProcess { except_pid } => {
+                log::info!(
+                    "All audio sessions except process {except_pid} unmuted after recording"
+                );
+            }
             MuteState::AudioWasAlreadyMutedByUser => {
                 log::info!("System audio was already muted, leaving muted");
             }
+            MuteState::UserOverrode => {
+                log::info!("User already took over mute/volume, leaving their choice alone");
+            }
             MuteState::NotMuting => {}
         }
 
@@ -180,9 +632,17 @@ impl AudioMuteManager {
 
 impl Drop for AudioMuteManager {
     fn drop(&mut self) {
-        // Try to unmute on drop (app exit/crash)
+        // Try to restore audio on drop (app exit/crash)
         let state = self.state.lock().unwrap();
-        if *state == MuteState::MutedByUs {
+        let should_restore = matches!(
+            *state,
+            MuteState::MutedByUs
+                | MuteState::MutedProcessByUs { .. }
+                | MuteState::MutedByScope { .. }
+                | MuteState::MutedAllExceptProcess { .. }
+                | MuteState::DuckedByUs { .. }
+        );
+        if should_restore {
             drop(state); // Release lock before calling unmute
             let _ = self.unmute();
         }
@@ -1,12 +1,16 @@
-use super::MuteState;
+use super::{MuteScope, MuteState};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) enum MuteTransitionAction {
     NoOp,
     SetMuted(bool),
+    SetMutedForProcess { pid: u32, muted: bool },
+    SetMutedForScope { scope: MuteScope, muted: bool },
+    SetMutedForAllExceptProcess { except_pid: u32, muted: bool },
+    SetVolume(f32),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) struct MuteTransitionDecision {
     pub(crate) next_state: MuteState,
     pub(crate) action: MuteTransitionAction,
@@ -30,7 +34,144 @@ pub(crate) fn decide_mute_transition(
                 }
             }
         }
-        MuteState::MutedByUs | MuteState::AudioWasAlreadyMutedByUser => MuteTransitionDecision {
+        // Already muting/ducking in some form - leave it alone.
+        MuteState::MutedByUs
+        | MuteState::MutedProcessByUs { .. }
+        | MuteState::MutedByScope { .. }
+        | MuteState::MutedAllExceptProcess { .. }
+        | MuteState::AudioWasAlreadyMutedByUser
+        | MuteState::DuckedByUs { .. }
+        | MuteState::UserOverrode => MuteTransitionDecision {
+            next_state: current_mute_state,
+            action: MuteTransitionAction::NoOp,
+        },
+    }
+}
+
+/// Decide the transition for muting only the audio session belonging to
+/// `pid`, instead of the whole output endpoint.
+pub(crate) fn decide_mute_process_transition(
+    current_mute_state: MuteState,
+    pid: u32,
+    audio_is_currently_muted: bool,
+) -> MuteTransitionDecision {
+    match current_mute_state {
+        MuteState::NotMuting => {
+            if audio_is_currently_muted {
+                MuteTransitionDecision {
+                    next_state: MuteState::AudioWasAlreadyMutedByUser,
+                    action: MuteTransitionAction::NoOp,
+                }
+            } else {
+                MuteTransitionDecision {
+                    next_state: MuteState::MutedProcessByUs { pid },
+                    action: MuteTransitionAction::SetMutedForProcess { pid, muted: true },
+                }
+            }
+        }
+        // Already muting/ducking in some form - leave it alone.
+        MuteState::MutedByUs
+        | MuteState::MutedProcessByUs { .. }
+        | MuteState::MutedByScope { .. }
+        | MuteState::MutedAllExceptProcess { .. }
+        | MuteState::AudioWasAlreadyMutedByUser
+        | MuteState::DuckedByUs { .. }
+        | MuteState::UserOverrode => MuteTransitionDecision {
+            next_state: current_mute_state,
+            action: MuteTransitionAction::NoOp,
+        },
+    }
+}
+
+/// Decide the transition for muting only the sessions matching `scope`,
+/// instead of the whole output endpoint.
+pub(crate) fn decide_mute_scope_transition(
+    current_mute_state: MuteState,
+    scope: MuteScope,
+    audio_is_currently_muted: bool,
+) -> MuteTransitionDecision {
+    match current_mute_state {
+        MuteState::NotMuting => {
+            if audio_is_currently_muted {
+                MuteTransitionDecision {
+                    next_state: MuteState::AudioWasAlreadyMutedByUser,
+                    action: MuteTransitionAction::NoOp,
+                }
+            } else {
+                MuteTransitionDecision {
+                    next_state: MuteState::MutedByScope { scope },
+                    action: MuteTransitionAction::SetMutedForScope { scope, muted: true },
+                }
+            }
+        }
+        // Already muting/ducking in some form - leave it alone.
+        MuteState::MutedByUs
+        | MuteState::MutedProcessByUs { .. }
+        | MuteState::MutedByScope { .. }
+        | MuteState::MutedAllExceptProcess { .. }
+        | MuteState::AudioWasAlreadyMutedByUser
+        | MuteState::DuckedByUs { .. }
+        | MuteState::UserOverrode => MuteTransitionDecision {
+            next_state: current_mute_state,
+            action: MuteTransitionAction::NoOp,
+        },
+    }
+}
+
+/// Decide the transition for muting every session except the one belonging
+/// to `except_pid`, instead of a single process, a scope, or the whole
+/// output endpoint. Unlike the other `decide_*` functions, this one doesn't
+/// take an "already muted" flag - there's no single boolean that captures
+/// whether "every session but one" is already muted, so we just proceed.
+pub(crate) fn decide_mute_all_except_process_transition(
+    current_mute_state: MuteState,
+    except_pid: u32,
+) -> MuteTransitionDecision {
+    match current_mute_state {
+        MuteState::NotMuting => MuteTransitionDecision {
+            next_state: MuteState::MutedAllExceptProcess { except_pid },
+            action: MuteTransitionAction::SetMutedForAllExceptProcess {
+                except_pid,
+                muted: true,
+            },
+        },
+        // Already muting/ducking in some form - leave it alone.
+        MuteState::MutedByUs
+        | MuteState::MutedProcessByUs { .. }
+        | MuteState::MutedByScope { .. }
+        | MuteState::MutedAllExceptProcess { .. }
+        | MuteState::AudioWasAlreadyMutedByUser
+        | MuteState::DuckedByUs { .. }
+        | MuteState::UserOverrode => MuteTransitionDecision {
+            next_state: current_mute_state,
+            action: MuteTransitionAction::NoOp,
+        },
+    }
+}
+
+/// Decide the transition for entering "duck" mode, where instead of a hard
+/// mute we lower the output volume to `duck_level` and remember the prior
+/// level so it can be restored exactly on unmute.
+pub(crate) fn decide_duck_transition(
+    current_mute_state: MuteState,
+    current_volume: f32,
+    duck_level: f32,
+) -> MuteTransitionDecision {
+    match current_mute_state {
+        MuteState::NotMuting => MuteTransitionDecision {
+            next_state: MuteState::DuckedByUs {
+                previous_level: current_volume,
+            },
+            action: MuteTransitionAction::SetVolume(duck_level),
+        },
+        // Already muting/ducking in some form - leave it alone.
+        MuteState::MutedByUs
+        | MuteState::MutedProcessByUs { .. }
+        | MuteState::MutedByScope { .. }
+        | MuteState::MutedAllExceptProcess { .. }
+        | MuteState::AudioWasAlreadyMutedByUser
+        | MuteState::DuckedByUs { .. }
+        | MuteState::UserOverrode => MuteTransitionDecision {
             next_state: current_mute_state,
             action: MuteTransitionAction::NoOp,
         },
@@ -43,9 +184,33 @@ pub(crate) fn decide_unmute_transition(current_mute_state: MuteState) -> MuteTra
             next_state: MuteState::NotMuting,
             action: MuteTransitionAction::SetMuted(false),
         },
-        MuteState::NotMuting | MuteState::AudioWasAlreadyMutedByUser => MuteTransitionDecision {
+        MuteState::MutedProcessByUs { pid } => MuteTransitionDecision {
             next_state: MuteState::NotMuting,
-            action: MuteTransitionAction::NoOp,
+            action: MuteTransitionAction::SetMutedForProcess { pid, muted: false },
         },
+        MuteState::MutedByScope { scope } => MuteTransitionDecision {
+            next_state: MuteState::NotMuting,
+            action: MuteTransitionAction::SetMutedForScope {
+                scope,
+                muted: false,
+            },
+        },
+        MuteState::MutedAllExceptProcess { except_pid } => MuteTransitionDecision {
+            next_state: MuteState::NotMuting,
+            action: MuteTransitionAction::SetMutedForAllExceptProcess {
+                except_pid,
+                muted: false,
+            },
+        },
+        MuteState::DuckedByUs { previous_level } => MuteTransitionDecision {
+            next_state: MuteState::NotMuting,
+            action: MuteTransitionAction::SetVolume(previous_level),
+        },
+        MuteState::NotMuting | MuteState::AudioWasAlreadyMutedByUser | MuteState::UserOverrode => {
+            MuteTransitionDecision {
+                next_state: MuteState::NotMuting,
+                action: MuteTransitionAction::NoOp,
+            }
+        }
     }
 }
@@ -0,0 +1,30 @@
+//! No-op audio mute control for platforms without a dedicated backend.
+
+use super::{AudioControlError, SystemAudioControl};
+
+/// Stub audio controller that reports unmuted and ignores mute requests.
+pub struct StubAudioController;
+
+impl StubAudioController {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SystemAudioControl for StubAudioController {
+    fn is_muted(&self) -> Result<bool, AudioControlError> {
+        Ok(false)
+    }
+
+    fn set_muted(&self, _muted: bool) -> Result<(), AudioControlError> {
+        Ok(())
+    }
+
+    fn get_volume(&self) -> Result<f32, AudioControlError> {
+        Ok(1.0)
+    }
+
+    fn set_volume(&self, _level: f32) -> Result<(), AudioControlError> {
+        Ok(())
+    }
+}
@@ -3,18 +3,50 @@
 //! Uses the Windows Audio Session API (WASAPI) to control the default audio
 //! output device's mute state.
 
-use super::{AudioControlError, SystemAudioControl};
+use super::{
+    AudioControlError, AudioSession, MuteScope, SessionReconciliationEvent, SystemAudioControl,
+};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use windows::core::{implement, GUID, PCWSTR};
 use windows::Win32::{
     Media::Audio::{
-        eConsole, eRender, Endpoints::IAudioEndpointVolume, IMMDevice, IMMDeviceEnumerator,
-        MMDeviceEnumerator,
+        eCommunications, eConsole, eRender, AudioSessionDisconnectReason, AudioSessionState,
+        AudioSessionStateActive,
+        Endpoints::{
+            IAudioEndpointVolume, IAudioEndpointVolumeCallback, IAudioEndpointVolumeCallback_Impl,
+        },
+        IAudioSessionControl2, IAudioSessionEvents, IAudioSessionEvents_Impl,
+        IAudioSessionManager2, IMMDevice, IMMDeviceEnumerator, ISimpleAudioVolume,
+        MMDeviceEnumerator, AUDIO_VOLUME_NOTIFICATION_DATA,
+    },
+    System::Com::{
+        CoCreateGuid, CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED,
     },
-    System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED},
 };
 
 /// Windows audio controller using WASAPI.
 pub struct WindowsAudioController {
+    /// The default render endpoint, re-activated per call to enumerate its
+    /// current audio sessions for per-process muting.
+    device: IMMDevice,
+    /// The default render endpoint for the "communications" role, used only
+    /// to tell which sessions on `device` belong to communications apps
+    /// (voice/video calling software) for `MuteScope::CommunicationsOnly`/
+    /// `MuteScope::MediaContent`.
+    communications_device: IMMDevice,
     endpoint_volume: IAudioEndpointVolume,
+    /// Opaque context passed to every `SetMute`/`SetMasterVolumeLevelScalar`
+    /// call so our own writes can be told apart from the user's in
+    /// `VolumeChangeCallback::OnNotify`/`SessionEventSink::OnSimpleVolumeChanged`.
+    event_context: GUID,
+    /// Kept alive for as long as we want notifications delivered; dropping it
+    /// would let COM release the callback.
+    registered_callback: Mutex<Option<IAudioEndpointVolumeCallback>>,
+    /// The session control and event sink currently subscribed for
+    /// per-process mute reconciliation, if any; kept alive for as long as we
+    /// want notifications delivered, same as `registered_callback` above.
+    registered_session_events: Mutex<Option<(IAudioSessionControl2, IAudioSessionEvents)>>,
 }
 
 // SAFETY: IAudioEndpointVolume is thread-safe when properly initialized with COM
@@ -56,9 +88,229 @@ impl WindowsAudioController {
                     ))
                 })?;
 
-            Ok(Self { endpoint_volume })
+            // Get the render endpoint for the "communications" role, used
+            // only to classify sessions on `device` by scope.
+            let communications_device: IMMDevice = enumerator
+                .GetDefaultAudioEndpoint(eRender, eCommunications)
+                .map_err(|e| {
+                    AudioControlError::InitializationFailed(format!(
+                        "Failed to get communications audio endpoint: {e}"
+                    ))
+                })?;
+
+            let event_context = CoCreateGuid().map_err(|e| {
+                AudioControlError::InitializationFailed(format!(
+                    "Failed to allocate event context GUID: {e}"
+                ))
+            })?;
+
+            Ok(Self {
+                device,
+                communications_device,
+                endpoint_volume,
+                event_context,
+                registered_callback: Mutex::new(None),
+                registered_session_events: Mutex::new(None),
+            })
+        }
+    }
+
+    /// Find the `IAudioSessionControl2` for the audio session belonging to
+    /// `pid`, if that process currently has one, by enumerating every
+    /// session on the default render endpoint.
+    fn find_session_control2(
+        &self,
+        pid: u32,
+    ) -> windows::core::Result<Option<IAudioSessionControl2>> {
+        unsafe {
+            let session_manager: IAudioSessionManager2 = self.device.Activate(CLSCTX_ALL, None)?;
+            let session_enumerator = session_manager.GetSessionEnumerator()?;
+            let session_count = session_enumerator.GetCount()?;
+
+            for index in 0..session_count {
+                let session_control = session_enumerator.GetSession(index)?;
+                let session_control2: IAudioSessionControl2 = session_control.cast()?;
+                if session_control2.GetProcessId()? == pid {
+                    return Ok(Some(session_control2));
+                }
+            }
+
+            Ok(None)
+        }
+    }
+
+    /// Enumerate every audio session on `device`, whatever its state
+    /// (active, inactive or expired).
+    fn enumerate_session_controls(
+        device: &IMMDevice,
+    ) -> windows::core::Result<Vec<IAudioSessionControl2>> {
+        unsafe {
+            let session_manager: IAudioSessionManager2 = device.Activate(CLSCTX_ALL, None)?;
+            let session_enumerator = session_manager.GetSessionEnumerator()?;
+            let session_count = session_enumerator.GetCount()?;
+
+            let mut session_controls = Vec::with_capacity(session_count as usize);
+            for index in 0..session_count {
+                let session_control = session_enumerator.GetSession(index)?;
+                session_controls.push(session_control.cast()?);
+            }
+
+            Ok(session_controls)
         }
     }
+
+    /// The set of process ids with a session on the "communications" role
+    /// endpoint, used to classify sessions on `self.device` as
+    /// `MuteScope::CommunicationsOnly` vs `MuteScope::MediaContent`.
+    fn communications_process_ids(&self) -> windows::core::Result<HashSet<u32>> {
+        Self::enumerate_session_controls(&self.communications_device)?
+            .into_iter()
+            .map(|session_control2| unsafe { session_control2.GetProcessId() })
+            .collect()
+    }
+
+    /// The sessions on `self.device` matching `scope`.
+    fn session_controls_for_scope(
+        &self,
+        scope: MuteScope,
+    ) -> windows::core::Result<Vec<IAudioSessionControl2>> {
+        let all_sessions = Self::enumerate_session_controls(&self.device)?;
+        let communications_pids = self.communications_process_ids()?;
+
+        all_sessions
+            .into_iter()
+            .map(|session_control2| {
+                let pid = unsafe { session_control2.GetProcessId()? };
+                let is_communications = communications_pids.contains(&pid);
+                let matches_scope = match scope {
+                    MuteScope::All => true,
+                    MuteScope::CommunicationsOnly => is_communications,
+                    MuteScope::MediaContent => !is_communications,
+                };
+                Ok((session_control2, matches_scope))
+            })
+            .collect::<windows::core::Result<Vec<_>>>()
+            .map(|sessions_with_scope_match| {
+                sessions_with_scope_match
+                    .into_iter()
+                    .filter_map(|(session_control2, matches_scope)| {
+                        matches_scope.then_some(session_control2)
+                    })
+                    .collect()
+            })
+    }
+}
+
+/// A human-readable name for an audio session: the session's own display
+/// name if it set one (most apps don't bother), falling back to the owning
+/// process's executable name, and finally the bare pid.
+fn session_display_name(session_control2: &IAudioSessionControl2, pid: u32) -> String {
+    let own_display_name = unsafe { session_control2.GetDisplayName() }
+        .ok()
+        .map(|name| name.to_string())
+        .filter(|name| !name.is_empty());
+
+    own_display_name.unwrap_or_else(|| {
+        crate::active_app_context::process_display_name_for_pid(pid)
+            .unwrap_or_else(|| format!("pid {pid}"))
+    })
+}
+
+/// COM callback object that fires on every mute/volume change on the
+/// endpoint it is registered against, including ones made by other
+/// processes or the user's OS volume mixer.
+#[implement(IAudioEndpointVolumeCallback)]
+struct VolumeChangeCallback {
+    event_context: GUID,
+    on_external_change: Box<dyn Fn() + Send + Sync>,
+}
+
+impl IAudioEndpointVolumeCallback_Impl for VolumeChangeCallback_Impl {
+    fn OnNotify(&self, notify: *mut AUDIO_VOLUME_NOTIFICATION_DATA) -> windows::core::Result<()> {
+        // SAFETY: WASAPI guarantees `notify` is a valid pointer for the
+        // duration of this call.
+        let guid_event_context = unsafe { (*notify).guidEventContext };
+        if guid_event_context != self.event_context {
+            (self.on_external_change)();
+        }
+        Ok(())
+    }
+}
+
+/// COM callback object that fires on per-process audio session events, used
+/// to reconcile `MuteState::MutedProcessByUs` when something other than us
+/// changes that session's mute state or it goes away entirely.
+#[implement(IAudioSessionEvents)]
+struct SessionEventSink {
+    /// Same opaque context passed to `ISimpleAudioVolume::SetMute` in
+    /// `set_muted_for_process`, so our own writes can be told apart from an
+    /// external change in `OnSimpleVolumeChanged`.
+    event_context: GUID,
+    on_event: Box<dyn Fn(SessionReconciliationEvent) + Send + Sync>,
+}
+
+impl IAudioSessionEvents_Impl for SessionEventSink_Impl {
+    fn OnDisplayNameChanged(
+        &self,
+        _new_display_name: &PCWSTR,
+        _event_context: *const GUID,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnIconPathChanged(
+        &self,
+        _new_icon_path: &PCWSTR,
+        _event_context: *const GUID,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnSimpleVolumeChanged(
+        &self,
+        _new_volume: f32,
+        new_mute: windows::core::BOOL,
+        event_context: *const GUID,
+    ) -> windows::core::Result<()> {
+        // SAFETY: WASAPI guarantees `event_context` is valid (or null) for
+        // the duration of this call.
+        let is_external =
+            event_context.is_null() || unsafe { *event_context } != self.event_context;
+        if is_external {
+            (self.on_event)(SessionReconciliationEvent::MuteChanged(new_mute.as_bool()));
+        }
+        Ok(())
+    }
+
+    fn OnChannelVolumeChanged(
+        &self,
+        _channel_count: u32,
+        _new_channel_volumes: *const f32,
+        _changed_channel: u32,
+        _event_context: *const GUID,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnGroupingParamChanged(
+        &self,
+        _new_grouping_param: *const GUID,
+        _event_context: *const GUID,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnStateChanged(&self, _new_state: AudioSessionState) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnSessionDisconnected(
+        &self,
+        _disconnect_reason: AudioSessionDisconnectReason,
+    ) -> windows::core::Result<()> {
+        (self.on_event)(SessionReconciliationEvent::Disconnected);
+        Ok(())
+    }
 }
 
 impl SystemAudioControl for WindowsAudioController {
@@ -74,8 +326,228 @@ impl SystemAudioControl for WindowsAudioController {
     fn set_muted(&self, muted: bool) -> Result<(), AudioControlError> {
         unsafe {
             self.endpoint_volume
-                .SetMute(muted, std::ptr::null())
+                .SetMute(muted, &self.event_context)
                 .map_err(|e| AudioControlError::SetPropertyFailed(format!("SetMute: {e}")))
         }
     }
+
+    fn get_volume(&self) -> Result<f32, AudioControlError> {
+        unsafe {
+            self.endpoint_volume
+                .GetMasterVolumeLevelScalar()
+                .map(|v| v.clamp(0.0, 1.0))
+                .map_err(|e| {
+                    AudioControlError::GetPropertyFailed(format!("GetMasterVolumeLevelScalar: {e}"))
+                })
+        }
+    }
+
+    fn set_volume(&self, level: f32) -> Result<(), AudioControlError> {
+        unsafe {
+            self.endpoint_volume
+                .SetMasterVolumeLevelScalar(level.clamp(0.0, 1.0), &self.event_context)
+                .map_err(|e| {
+                    AudioControlError::SetPropertyFailed(format!("SetMasterVolumeLevelScalar: {e}"))
+                })
+        }
+    }
+
+    fn is_muted_for_process(&self, pid: u32) -> Result<bool, AudioControlError> {
+        let session_control2 = self
+            .find_session_control2(pid)
+            .map_err(|e| {
+                AudioControlError::GetPropertyFailed(format!(
+                    "Failed to find audio session for process {pid}: {e}"
+                ))
+            })?
+            .ok_or_else(|| {
+                AudioControlError::GetPropertyFailed(format!(
+                    "No audio session found for process {pid}"
+                ))
+            })?;
+
+        unsafe {
+            let session_volume: ISimpleAudioVolume = session_control2
+                .cast()
+                .map_err(|e| AudioControlError::GetPropertyFailed(format!("cast: {e}")))?;
+            session_volume
+                .GetMute()
+                .map(windows::core::BOOL::as_bool)
+                .map_err(|e| AudioControlError::GetPropertyFailed(format!("GetMute: {e}")))
+        }
+    }
+
+    fn set_muted_for_process(&self, pid: u32, muted: bool) -> Result<(), AudioControlError> {
+        let session_control2 = self
+            .find_session_control2(pid)
+            .map_err(|e| {
+                AudioControlError::SetPropertyFailed(format!(
+                    "Failed to find audio session for process {pid}: {e}"
+                ))
+            })?
+            .ok_or_else(|| {
+                AudioControlError::SetPropertyFailed(format!(
+                    "No audio session found for process {pid}"
+                ))
+            })?;
+
+        unsafe {
+            let session_volume: ISimpleAudioVolume = session_control2
+                .cast()
+                .map_err(|e| AudioControlError::SetPropertyFailed(format!("cast: {e}")))?;
+            session_volume
+                .SetMute(muted, &self.event_context)
+                .map_err(|e| AudioControlError::SetPropertyFailed(format!("SetMute: {e}")))
+        }
+    }
+
+    fn is_muted_for_scope(&self, scope: MuteScope) -> Result<bool, AudioControlError> {
+        if scope == MuteScope::All {
+            return self.is_muted();
+        }
+
+        let session_controls = self.session_controls_for_scope(scope).map_err(|e| {
+            AudioControlError::GetPropertyFailed(format!(
+                "Failed to enumerate sessions for scope {scope:?}: {e}"
+            ))
+        })?;
+
+        unsafe {
+            for session_control2 in &session_controls {
+                let session_volume: ISimpleAudioVolume = session_control2
+                    .cast()
+                    .map_err(|e| AudioControlError::GetPropertyFailed(format!("cast: {e}")))?;
+                if !session_volume
+                    .GetMute()
+                    .map_err(|e| AudioControlError::GetPropertyFailed(format!("GetMute: {e}")))?
+                    .as_bool()
+                {
+                    return Ok(false);
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn set_muted_for_scope(&self, scope: MuteScope, muted: bool) -> Result<(), AudioControlError> {
+        if scope == MuteScope::All {
+            return self.set_muted(muted);
+        }
+
+        let session_controls = self.session_controls_for_scope(scope).map_err(|e| {
+            AudioControlError::SetPropertyFailed(format!(
+                "Failed to enumerate sessions for scope {scope:?}: {e}"
+            ))
+        })?;
+
+        unsafe {
+            for session_control2 in &session_controls {
+                let session_volume: ISimpleAudioVolume = session_control2
+                    .cast()
+                    .map_err(|e| AudioControlError::SetPropertyFailed(format!("cast: {e}")))?;
+                session_volume
+                    .SetMute(muted, &self.event_context)
+                    .map_err(|e| AudioControlError::SetPropertyFailed(format!("SetMute: {e}")))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_device_active(&self) -> Result<bool, AudioControlError> {
+        let session_controls = Self::enumerate_session_controls(&self.device).map_err(|e| {
+            AudioControlError::GetPropertyFailed(format!("Failed to enumerate audio sessions: {e}"))
+        })?;
+
+        for session_control2 in session_controls {
+            let state = unsafe { session_control2.GetState() }
+                .map_err(|e| AudioControlError::GetPropertyFailed(format!("GetState: {e}")))?;
+            if state == AudioSessionStateActive {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn enumerate_sessions(&self) -> Result<Vec<AudioSession>, AudioControlError> {
+        let session_controls = Self::enumerate_session_controls(&self.device).map_err(|e| {
+            AudioControlError::GetPropertyFailed(format!("Failed to enumerate audio sessions: {e}"))
+        })?;
+
+        session_controls
+            .into_iter()
+            .map(|session_control2| unsafe {
+                let pid = session_control2.GetProcessId().map_err(|e| {
+                    AudioControlError::GetPropertyFailed(format!("GetProcessId: {e}"))
+                })?;
+                let session_volume: ISimpleAudioVolume = session_control2
+                    .cast()
+                    .map_err(|e| AudioControlError::GetPropertyFailed(format!("cast: {e}")))?;
+                let muted = session_volume
+                    .GetMute()
+                    .map_err(|e| AudioControlError::GetPropertyFailed(format!("GetMute: {e}")))?
+                    .as_bool();
+                let name = session_display_name(&session_control2, pid);
+
+                Ok(AudioSession { pid, name, muted })
+            })
+            .collect()
+    }
+
+    fn register_session_event_listener(
+        &self,
+        pid: u32,
+        on_event: Box<dyn Fn(SessionReconciliationEvent) + Send + Sync>,
+    ) {
+        let session_control2 = match self.find_session_control2(pid) {
+            Ok(Some(control)) => control,
+            Ok(None) => {
+                log::warn!("No audio session found for process {pid}, skipping reconciliation");
+                return;
+            }
+            Err(e) => {
+                log::warn!("Failed to find audio session for process {pid}: {e}");
+                return;
+            }
+        };
+
+        let events: IAudioSessionEvents = SessionEventSink {
+            event_context: self.event_context,
+            on_event,
+        }
+        .into();
+
+        let register_result = unsafe { session_control2.RegisterAudioSessionNotification(&events) };
+
+        match register_result {
+            Ok(()) => {
+                *self.registered_session_events.lock().unwrap() = Some((session_control2, events));
+            }
+            Err(e) => {
+                log::warn!("Failed to register audio session notification for process {pid}: {e}");
+            }
+        }
+    }
+
+    fn register_external_change_listener(&self, on_external_change: Box<dyn Fn() + Send + Sync>) {
+        let callback: IAudioEndpointVolumeCallback = VolumeChangeCallback {
+            event_context: self.event_context,
+            on_external_change,
+        }
+        .into();
+
+        let register_result =
+            unsafe { self.endpoint_volume.RegisterControlChangeNotify(&callback) };
+
+        match register_result {
+            Ok(()) => {
+                *self.registered_callback.lock().unwrap() = Some(callback);
+            }
+            Err(e) => {
+                log::warn!("Failed to register audio endpoint volume callback: {e}");
+            }
+        }
+    }
 }
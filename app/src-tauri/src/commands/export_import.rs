@@ -2,13 +2,15 @@ use anyhow::Context;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, State};
 
 use crate::config_sync::{ConfigSync, DEFAULT_STT_TIMEOUT_SECONDS};
-use crate::history::{HistoryEntry, HistoryImportResult, HistoryImportStrategy, HistoryStorage};
+use crate::history::{
+    HistoryEntry, HistoryExportFormat, HistoryImportResult, HistoryImportStrategy, HistoryStorage,
+};
 use crate::settings::{
     AppSettings, CleanupPromptSections, HttpSyncedSetting, LocalOnlySetting, PromptMode,
-    PromptSection, PromptSectionType, RtviSyncedSetting, SettingClass,
+    PromptSection, PromptSectionType, RtviSyncedSetting, SettingClass, SettingsManager,
 };
 
 #[cfg(desktop)]
@@ -19,7 +21,7 @@ use tauri_plugin_store::StoreExt;
 // ============================================================================
 
 /// Current export format version - increment when format changes
-const EXPORT_VERSION: u32 = 1;
+const EXPORT_VERSION: u32 = 2;
 
 /// Type identifier for settings export files
 const SETTINGS_EXPORT_TYPE: &str = "tambourine-settings";
@@ -27,10 +29,94 @@ const SETTINGS_EXPORT_TYPE: &str = "tambourine-settings";
 /// Type identifier for history export files
 const HISTORY_EXPORT_TYPE: &str = "tambourine-history";
 
+/// Type identifier for prompt profile bundle export files
+const PROMPT_BUNDLE_EXPORT_TYPE: &str = "tambourine-prompt-bundle";
+
 /// HTML comment prefix for prompt files
 const PROMPT_COMMENT_PREFIX: &str = "<!-- tambourine-prompt: ";
 const PROMPT_COMMENT_SUFFIX: &str = " -->";
 
+/// Parse a hand-edited settings/history export, tolerating `//` and `/* */`
+/// comments and trailing commas in arrays and objects - both are easy
+/// mistakes to make when editing an exported JSON file by hand, and
+/// `serde_json` rejects them outright. Export always produces strict JSON;
+/// only this import path is lenient. `kind` names the file in error
+/// messages, e.g. "settings".
+fn parse_lenient_json(content: &str, kind: &str) -> Result<serde_json::Value, String> {
+    serde_json_lenient::from_str(content).map_err(|e| format!("Failed to parse {kind} file: {e}"))
+}
+
+/// Export/import file format for settings and history backups, selected by
+/// file extension on the export side (`.json`/`.yaml`) and auto-detected on
+/// import (see `parse_export_document`) so re-importing never requires
+/// knowing which one was used.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFileFormat {
+    Json,
+    Yaml,
+}
+
+/// Serialize `value` as the versioned export file format requested.
+fn serialize_export_document<T: Serialize>(
+    value: &T,
+    format: ExportFileFormat,
+    kind: &str,
+) -> Result<String, String> {
+    match format {
+        ExportFileFormat::Json => serde_json::to_string_pretty(value)
+            .map_err(|e| format!("Failed to serialize {kind}: {e}")),
+        ExportFileFormat::Yaml => {
+            serde_yaml::to_string(value).map_err(|e| format!("Failed to serialize {kind}: {e}"))
+        }
+    }
+}
+
+/// Parse a settings/history/prompt-bundle backup as either JSON (tolerating
+/// hand-edits, see `parse_lenient_json`) or YAML. JSON is tried first since
+/// it's the default export format; when neither parses, the error carries
+/// both the JSON and the YAML parse failure.
+fn parse_export_document(content: &str, kind: &str) -> Result<serde_json::Value, String> {
+    parse_lenient_json(content, kind).or_else(|json_error| {
+        serde_yaml::from_str(content).map_err(|yaml_error| {
+            format!("Failed to parse {kind} file as JSON ({json_error}) or YAML ({yaml_error})")
+        })
+    })
+}
+
+/// Parse, type/version-check, and migrate an export file up to
+/// `EXPORT_VERSION`, stopping short of the final `serde_json::from_value`
+/// into its typed shape so callers can use the same document for either a
+/// real import or a dry-run preview.
+fn parse_and_migrate_export_document(
+    content: &str,
+    kind: &str,
+    expected_type: &str,
+    migrations: &[Migration],
+) -> Result<serde_json::Value, String> {
+    let document = parse_export_document(content, kind)?;
+
+    let file_type = document.get("type").and_then(serde_json::Value::as_str);
+    if file_type != Some(expected_type) {
+        return Err(format!(
+            "Invalid file type: expected '{expected_type}', got '{file_type:?}'"
+        ));
+    }
+
+    let version = document
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .ok_or_else(|| format!("{kind} file is missing a version field"))? as u32;
+    if version > EXPORT_VERSION {
+        return Err(format!(
+            "Unsupported version: file is version {version}, max supported is {EXPORT_VERSION}"
+        ));
+    }
+
+    apply_migrations(document, version, migrations)
+        .map_err(|e| format!("Failed to migrate {kind} file: {e:#}"))
+}
+
 /// Settings data for export (excludes prompts - they're exported as .md files)
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +131,11 @@ pub struct SettingsExportData {
     pub stt_provider: String,
     pub llm_provider: String,
     pub auto_mute_audio: bool,
+    /// Accepts either a plain number of seconds (for backward compatibility)
+    /// or a human-readable duration string like `"30s"`/`"1m30s"`/`"500ms"`,
+    /// and always serializes back out in the latter form - see
+    /// `crate::duration`.
+    #[serde(with = "crate::duration::seconds_option")]
     pub stt_timeout_seconds: Option<f64>,
     pub llm_formatting_enabled: bool,
     pub server_url: String,
@@ -114,10 +205,78 @@ pub struct HistoryExportFile {
     pub file_type: String,
     pub version: u32,
     pub exported_at: DateTime<Utc>,
-    pub entry_count: usize,
+    /// Renamed from `entry_count` in version 1, see `migrate_history_v1_to_v2`.
+    pub count: usize,
     pub data: Vec<HistoryEntry>,
 }
 
+/// A bundle of named prompt profiles (see `PromptProfile` commands in
+/// `commands::settings`) for export/import, so a user can move their whole
+/// set of saved prompt setups ("email", "code", "dictation", ...) to another
+/// machine in one file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptBundleExportFile {
+    #[serde(rename = "type")]
+    pub file_type: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub profiles: HashMap<String, CleanupPromptSections>,
+}
+
+// ============================================================================
+// VERSIONED EXPORT FILE MIGRATIONS
+// ============================================================================
+
+/// A single upgrade step for an export document, from one version to the
+/// next. Migrations must be pure and idempotent, and must preserve any
+/// fields they don't recognize so unrelated future changes round-trip.
+type Migration = fn(serde_json::Value) -> anyhow::Result<serde_json::Value>;
+
+/// Ordered migration chain for settings export files. `SETTINGS_MIGRATIONS[i]`
+/// upgrades a document from version `i + 1` to `i + 2`. Empty: a v1 settings
+/// export still deserializes as is (`SettingsExportData` is
+/// `#[serde(default)]`), so `apply_migrations` only restamps the version.
+const SETTINGS_MIGRATIONS: &[Migration] = &[];
+
+/// Ordered migration chain for history export files, following the same
+/// `[i]` upgrades `i + 1` -> `i + 2` convention as `SETTINGS_MIGRATIONS`.
+const HISTORY_MIGRATIONS: &[Migration] = &[migrate_history_v1_to_v2];
+
+/// Ordered migration chain for prompt bundle export files, following the
+/// same convention as `SETTINGS_MIGRATIONS`. Empty since the bundle format
+/// was introduced at the current `EXPORT_VERSION`.
+const PROMPT_BUNDLE_MIGRATIONS: &[Migration] = &[];
+
+/// v1 -> v2: renames the top-level `entry_count` field to `count`.
+fn migrate_history_v1_to_v2(mut document: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+    let object = document
+        .as_object_mut()
+        .context("history export document is not a JSON object")?;
+    if let Some(entry_count) = object.remove("entry_count") {
+        object.entry("count").or_insert(entry_count);
+    }
+    Ok(document)
+}
+
+/// Applies every migration needed to bring `document` (currently at
+/// `from_version`) up to `EXPORT_VERSION`, then stamps the result with the
+/// current version. Callers are expected to have already rejected
+/// `from_version > EXPORT_VERSION`.
+fn apply_migrations(
+    mut document: serde_json::Value,
+    from_version: u32,
+    migrations: &[Migration],
+) -> anyhow::Result<serde_json::Value> {
+    let already_applied = from_version.saturating_sub(1) as usize;
+    for migration in migrations.iter().skip(already_applied) {
+        document = migration(document)?;
+    }
+    if let Some(object) = document.as_object_mut() {
+        object.insert("version".to_string(), serde_json::json!(EXPORT_VERSION));
+    }
+    Ok(document)
+}
+
 // ============================================================================
 // IMPORT RESULT TYPES
 // ============================================================================
@@ -128,6 +287,7 @@ pub struct HistoryExportFile {
 pub enum DetectedFileType {
     Settings,
     History,
+    PromptBundle,
     Unknown,
 }
 
@@ -180,7 +340,35 @@ pub struct RuntimeApplyOutcome {
 }
 
 pub type ImportSettingsOutcome = RuntimeApplyOutcome;
-pub type FactoryResetOutcome = RuntimeApplyOutcome;
+
+/// Factory reset summary: the runtime-apply outcome plus the path of the
+/// pre-reset backup snapshot (see `write_pre_reset_backup`), so the caller
+/// can offer an undo via `restore_last_backup`. `backup_path` is `None` if
+/// the backup itself failed - a failed backup does not block the reset.
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct FactoryResetOutcome {
+    pub warnings: Vec<RuntimeApplyWarning>,
+    pub runtime_actions_applied: Vec<RuntimeActionApplied>,
+    pub backup_path: Option<String>,
+}
+
+/// Per-setting entry in a `preview_import_settings` dry run.
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingDiffEntry {
+    #[serde(serialize_with = "serialize_setting_class_as_storage_key_name")]
+    pub setting_key: SettingClass,
+    pub current_value: Option<serde_json::Value>,
+    pub incoming_value: serde_json::Value,
+    pub would_change: bool,
+}
+
+/// Result of previewing a settings import: which settings would change and
+/// which runtime side effects `import_settings` would go on to apply.
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsImportPreview {
+    pub setting_diffs: Vec<SettingDiffEntry>,
+    pub runtime_side_effects: Vec<RuntimeApplyAction>,
+}
 
 // ============================================================================
 // HELPER FOR FILE TYPE DETECTION
@@ -198,10 +386,14 @@ struct FileTypeProbe {
 // COMMANDS
 // ============================================================================
 
-/// Generate settings export JSON string (excludes prompts - they're exported as .md files)
+/// Generate a settings export in the requested format (excludes prompts -
+/// they're exported as .md files)
 #[cfg(desktop)]
 #[tauri::command]
-pub fn generate_settings_export(app: AppHandle) -> Result<String, String> {
+pub fn generate_settings_export(
+    app: AppHandle,
+    format: ExportFileFormat,
+) -> Result<String, String> {
     use super::settings::get_settings;
 
     let settings = get_settings(app)?;
@@ -214,18 +406,21 @@ pub fn generate_settings_export(app: AppHandle) -> Result<String, String> {
         data: export_data,
     };
 
-    serde_json::to_string_pretty(&export).map_err(|e| format!("Failed to serialize settings: {e}"))
+    serialize_export_document(&export, format, "settings")
 }
 
 #[cfg(not(desktop))]
 #[tauri::command]
-pub fn generate_settings_export(_app: AppHandle) -> Result<String, String> {
+pub fn generate_settings_export(
+    _app: AppHandle,
+    _format: ExportFileFormat,
+) -> Result<String, String> {
     Err("Not supported on this platform".to_string())
 }
 
-/// Generate history export JSON string
+/// Generate a history export in the requested format
 #[tauri::command]
-pub fn generate_history_export(app: AppHandle) -> Result<String, String> {
+pub fn generate_history_export(app: AppHandle, format: ExportFileFormat) -> Result<String, String> {
     let history_storage = app.state::<HistoryStorage>();
     let entries = history_storage
         .get_all(None)
@@ -235,11 +430,43 @@ pub fn generate_history_export(app: AppHandle) -> Result<String, String> {
         file_type: HISTORY_EXPORT_TYPE.to_string(),
         version: EXPORT_VERSION,
         exported_at: Utc::now(),
-        entry_count: entries.len(),
+        count: entries.len(),
         data: entries,
     };
 
-    serde_json::to_string_pretty(&export).map_err(|e| format!("Failed to serialize history: {e}"))
+    serialize_export_document(&export, format, "history")
+}
+
+/// Export history entries as a plain JSON array, CSV or Markdown (not the
+/// versioned `HistoryExportFile` wrapper `generate_history_export` produces)
+/// for backup/migration across machines.
+#[tauri::command]
+pub fn export_history_as(format: HistoryExportFormat, app: AppHandle) -> Result<String, String> {
+    let history_storage = app.state::<HistoryStorage>();
+    history_storage
+        .export_entries(format)
+        .map_err(|e| format!("Failed to export history: {e}"))
+}
+
+/// Generate a bundle export carrying every saved prompt profile (see
+/// `PromptProfile` commands in `commands::settings`), in the requested
+/// format, for moving a user's whole set of named prompt setups to another
+/// machine.
+#[tauri::command]
+pub fn generate_prompt_bundle_export(
+    settings_manager: State<'_, SettingsManager>,
+    format: ExportFileFormat,
+) -> Result<String, String> {
+    let profiles = settings_manager.get_all_prompt_profiles()?;
+
+    let export = PromptBundleExportFile {
+        file_type: PROMPT_BUNDLE_EXPORT_TYPE.to_string(),
+        version: EXPORT_VERSION,
+        exported_at: Utc::now(),
+        profiles,
+    };
+
+    serialize_export_document(&export, format, "prompt bundle")
 }
 
 /// Generate prompt exports as markdown content with HTML comment headers.
@@ -387,12 +614,10 @@ pub async fn import_prompt(
     )
     .map_err(|error| format!("Failed to save imported prompt section: {error:#}"))?;
 
-    // Sync to server if connected
-    let sync = config_sync.read().await;
-    if sync.is_connected() {
-        if let Err(e) = sync.sync_prompt_sections(&sections).await {
-            log::warn!("Failed to sync prompt after import: {e}");
-        }
+    // Sync to server, queueing for retry if offline or the request fails
+    let mut sync = config_sync.write().await;
+    if let Err(e) = sync.sync_prompt_sections(&sections).await {
+        log::warn!("Failed to sync prompt after import: {e}");
     }
 
     log::info!("Imported prompt for section: {}", section.as_str());
@@ -410,11 +635,17 @@ pub async fn import_prompt(
     Err("Not supported on this platform".to_string())
 }
 
-/// Detect the type of an export file from its content
+/// Detect the type of an export file from its content. Uses
+/// `parse_export_document` rather than parsing JSON directly so a YAML
+/// export (`ExportFileFormat::Yaml`) is detected the same as JSON instead of
+/// always coming back `Unknown`.
 #[tauri::command]
 pub fn detect_export_file_type(content: String) -> DetectedFileType {
-    match serde_json::from_str::<FileTypeProbe>(&content) {
-        Ok(probe) => match probe.file_type.as_deref() {
+    let probe = parse_export_document(&content, "export")
+        .ok()
+        .and_then(|document| serde_json::from_value::<FileTypeProbe>(document).ok());
+    match probe {
+        Some(probe) => match probe.file_type.as_deref() {
             Some(SETTINGS_EXPORT_TYPE) => {
                 if probe.version.is_some_and(|v| v <= EXPORT_VERSION) {
                     DetectedFileType::Settings
@@ -439,13 +670,25 @@ pub fn detect_export_file_type(content: String) -> DetectedFileType {
                     DetectedFileType::Unknown
                 }
             }
+            Some(PROMPT_BUNDLE_EXPORT_TYPE) => {
+                if probe.version.is_some_and(|v| v <= EXPORT_VERSION) {
+                    DetectedFileType::PromptBundle
+                } else {
+                    log::warn!(
+                        "Prompt bundle file version {} is newer than supported version {}",
+                        probe.version.unwrap_or(0),
+                        EXPORT_VERSION
+                    );
+                    DetectedFileType::Unknown
+                }
+            }
             _ => {
                 log::warn!("Unknown file type: {:?}", probe.file_type);
                 DetectedFileType::Unknown
             }
         },
-        Err(e) => {
-            log::warn!("Failed to parse file type: {e}");
+        None => {
+            log::warn!("Failed to parse file type");
             DetectedFileType::Unknown
         }
     }
@@ -526,6 +769,83 @@ fn serialized_value_for_setting_class(
     })
 }
 
+#[cfg(desktop)]
+type SettingsStoreHandle = std::sync::Arc<tauri_plugin_store::Store<tauri::Wry>>;
+
+/// Read the current stored value of every `setting_class`, to restore if a
+/// write that's about to happen fails partway through.
+#[cfg(desktop)]
+fn snapshot_setting_classes(
+    store: &SettingsStoreHandle,
+    setting_classes: &[SettingClass],
+) -> Vec<(SettingClass, Option<serde_json::Value>)> {
+    setting_classes
+        .iter()
+        .map(|setting_class| (*setting_class, store.get(setting_class.storage_key_name())))
+        .collect()
+}
+
+/// Restore a snapshot taken by `snapshot_setting_classes`, clearing any key
+/// that had no prior value. Does not save - the caller decides when.
+#[cfg(desktop)]
+fn restore_setting_classes_snapshot(
+    store: &SettingsStoreHandle,
+    snapshot: &[(SettingClass, Option<serde_json::Value>)],
+) {
+    for (setting_class, previous_value) in snapshot {
+        match previous_value {
+            Some(value) => {
+                store.set(setting_class.storage_key_name(), value.clone());
+            }
+            None => {
+                store.delete(setting_class.storage_key_name());
+            }
+        }
+    }
+}
+
+/// Write `setting_classes` from `settings` into `store` and save, rolling
+/// the store back to its pre-write values (and saving that rollback) if any
+/// step fails - so a failed import or factory reset never leaves the store
+/// in a mixed old/new state. The returned error says so explicitly.
+#[cfg(desktop)]
+fn write_setting_classes_atomically(
+    store: &SettingsStoreHandle,
+    settings: &AppSettings,
+    setting_classes: &[SettingClass],
+    save_error_context: &str,
+) -> Result<(), String> {
+    let snapshot = snapshot_setting_classes(store, setting_classes);
+
+    let write_result = write_setting_classes_to_store(
+        settings,
+        setting_classes,
+        |setting_class, setting_value| {
+            store.set(setting_class.storage_key_name(), setting_value);
+        },
+    )
+    .map_err(|e| format!("Failed to serialize setting: {e:#}"))
+    .and_then(|()| {
+        store
+            .save()
+            .map_err(|e| format!("Failed to save {save_error_context}: {e}"))
+    });
+
+    let Err(error) = write_result else {
+        return Ok(());
+    };
+
+    restore_setting_classes_snapshot(store, &snapshot);
+    if let Err(rollback_error) = store.save() {
+        return Err(format!(
+            "{error}; rollback also failed to save ({rollback_error}) - settings store may be in a mixed state"
+        ));
+    }
+    Err(format!(
+        "Import rolled back, settings left unchanged: {error}"
+    ))
+}
+
 fn write_setting_classes_to_store(
     app_settings: &AppSettings,
     setting_classes: &[SettingClass],
@@ -573,6 +893,22 @@ where
     serializer.serialize_str(setting_class.storage_key_name())
 }
 
+/// The runtime side effects `apply_runtime_side_effects` would attempt for
+/// `settings`, without actually running any of the network/watcher calls -
+/// used by `preview_import_settings` to describe what an import would do.
+fn preview_runtime_side_effects(settings: &AppSettings) -> Vec<RuntimeApplyAction> {
+    let mut actions = vec![if settings.send_active_app_context_enabled {
+        RuntimeApplyAction::FocusWatcherEnabled
+    } else {
+        RuntimeApplyAction::FocusWatcherDisabled
+    }];
+    if settings.stt_timeout_seconds.is_some() {
+        actions.push(RuntimeApplyAction::SttTimeoutSynced);
+    }
+    actions.push(RuntimeApplyAction::LlmFormattingSynced);
+    actions
+}
+
 #[cfg(desktop)]
 async fn apply_runtime_side_effects(
     app: &AppHandle,
@@ -612,10 +948,9 @@ async fn apply_runtime_side_effects(
         }
     }
 
-    let sync = config_sync.read().await;
-    if !sync.is_connected() {
-        return runtime_apply_outcome;
-    }
+    // Always attempt the sync, even while offline: `sync_*` queues the
+    // operation for retry instead of dropping it in that case.
+    let mut sync = config_sync.write().await;
 
     if let Some(prompt_sections) = prompt_sections_to_sync {
         match sync.sync_prompt_sections(prompt_sections).await {
@@ -695,48 +1030,32 @@ pub async fn import_settings(
     content: String,
     config_sync: tauri::State<'_, ConfigSync>,
 ) -> Result<ImportSettingsOutcome, String> {
-    // Parse the export file
-    let export: SettingsExportFile = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse settings file: {e}"))?;
-
-    // Validate file type
-    if export.file_type != SETTINGS_EXPORT_TYPE {
-        return Err(format!(
-            "Invalid file type: expected '{}', got '{}'",
-            SETTINGS_EXPORT_TYPE, export.file_type
-        ));
-    }
-
-    // Validate version
-    if export.version > EXPORT_VERSION {
-        return Err(format!(
-            "Unsupported version: file is version {}, max supported is {}",
-            export.version, EXPORT_VERSION
-        ));
-    }
+    let document = parse_and_migrate_export_document(
+        &content,
+        "settings",
+        SETTINGS_EXPORT_TYPE,
+        SETTINGS_MIGRATIONS,
+    )?;
+    let export: SettingsExportFile = serde_json::from_value(document)
+        .map_err(|e| format!("Failed to parse migrated settings file: {e}"))?;
 
     // Get store
     let store = app
         .store("settings.json")
         .map_err(|e| format!("Failed to get store: {e}"))?;
 
-    // Import each setting
+    // Import each setting. Note: cleanup_prompt_sections is not imported
+    // here - prompts come from .md files.
     let imported_settings: AppSettings = export.data.into();
 
-    // Save each setting individually so we can handle defaults properly.
-    // Note: cleanup_prompt_sections is not imported here - prompts come from .md files.
-    write_setting_classes_to_store(
+    // Snapshots and rolls back to the pre-import values if any step fails,
+    // so a bad import file never leaves the store half-old, half-new.
+    write_setting_classes_atomically(
+        &store,
         &imported_settings,
         &IMPORT_EXPORT_SETTING_CLASSES,
-        |setting_class, setting_value| {
-            store.set(setting_class.storage_key_name(), setting_value);
-        },
-    )
-    .map_err(|error| format!("Failed to serialize setting for import: {error:#}"))?;
-
-    store
-        .save()
-        .map_err(|e| format!("Failed to save settings: {e}"))?;
+        "settings",
+    )?;
 
     let runtime_apply_outcome = apply_runtime_side_effects(
         &app,
@@ -770,6 +1089,60 @@ pub async fn import_settings(
     Err("Not supported on this platform".to_string())
 }
 
+/// Parse and validate a settings export exactly as `import_settings` would,
+/// but report the per-setting diff and the runtime side effects it would
+/// trigger instead of writing anything, so the UI can show a confirmation
+/// screen before committing to the import.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn preview_import_settings(
+    app: AppHandle,
+    content: String,
+) -> Result<SettingsImportPreview, String> {
+    let document = parse_and_migrate_export_document(
+        &content,
+        "settings",
+        SETTINGS_EXPORT_TYPE,
+        SETTINGS_MIGRATIONS,
+    )?;
+    let export: SettingsExportFile = serde_json::from_value(document)
+        .map_err(|e| format!("Failed to parse migrated settings file: {e}"))?;
+
+    let store = app
+        .store("settings.json")
+        .map_err(|e| format!("Failed to get store: {e}"))?;
+    let imported_settings: AppSettings = export.data.into();
+
+    let mut setting_diffs = Vec::with_capacity(IMPORT_EXPORT_SETTING_CLASSES.len());
+    for setting_class in &IMPORT_EXPORT_SETTING_CLASSES {
+        let setting_class = *setting_class;
+        let incoming_value = serialized_value_for_setting_class(&imported_settings, setting_class)
+            .map_err(|e| format!("Failed to serialize setting for preview: {e:#}"))?;
+        let current_value = store.get(setting_class.storage_key_name());
+        let would_change = current_value.as_ref() != Some(&incoming_value);
+        setting_diffs.push(SettingDiffEntry {
+            setting_key: setting_class,
+            current_value,
+            incoming_value,
+            would_change,
+        });
+    }
+
+    Ok(SettingsImportPreview {
+        setting_diffs,
+        runtime_side_effects: preview_runtime_side_effects(&imported_settings),
+    })
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn preview_import_settings(
+    _app: AppHandle,
+    _content: String,
+) -> Result<SettingsImportPreview, String> {
+    Err("Not supported on this platform".to_string())
+}
+
 /// Import history from a JSON string with the specified merge strategy
 #[tauri::command]
 pub fn import_history(
@@ -777,25 +1150,14 @@ pub fn import_history(
     content: String,
     strategy: HistoryImportStrategy,
 ) -> Result<HistoryImportResult, String> {
-    // Parse the export file
-    let export: HistoryExportFile =
-        serde_json::from_str(&content).map_err(|e| format!("Failed to parse history file: {e}"))?;
-
-    // Validate file type
-    if export.file_type != HISTORY_EXPORT_TYPE {
-        return Err(format!(
-            "Invalid file type: expected '{}', got '{}'",
-            HISTORY_EXPORT_TYPE, export.file_type
-        ));
-    }
-
-    // Validate version
-    if export.version > EXPORT_VERSION {
-        return Err(format!(
-            "Unsupported version: file is version {}, max supported is {}",
-            export.version, EXPORT_VERSION
-        ));
-    }
+    let document = parse_and_migrate_export_document(
+        &content,
+        "history",
+        HISTORY_EXPORT_TYPE,
+        HISTORY_MIGRATIONS,
+    )?;
+    let export: HistoryExportFile = serde_json::from_value(document)
+        .map_err(|e| format!("Failed to parse migrated history file: {e}"))?;
 
     let history_storage = app.state::<HistoryStorage>();
     let result = history_storage
@@ -812,6 +1174,192 @@ pub fn import_history(
     Ok(result)
 }
 
+/// Preview an `import_history` call for `strategy` without mutating stored
+/// history, so the UI can show import/skip counts before committing.
+#[tauri::command]
+pub fn preview_import_history(
+    app: AppHandle,
+    content: String,
+    strategy: HistoryImportStrategy,
+) -> Result<HistoryImportResult, String> {
+    let document = parse_and_migrate_export_document(
+        &content,
+        "history",
+        HISTORY_EXPORT_TYPE,
+        HISTORY_MIGRATIONS,
+    )?;
+    let export: HistoryExportFile = serde_json::from_value(document)
+        .map_err(|e| format!("Failed to parse migrated history file: {e}"))?;
+
+    let history_storage = app.state::<HistoryStorage>();
+    history_storage
+        .preview_import(&export.data, strategy)
+        .map_err(|error| error.to_string())
+}
+
+/// Import a prompt profile bundle, adding/overwriting each profile it
+/// contains. Returns the names of the profiles that were imported.
+#[tauri::command]
+pub fn import_prompt_bundle(
+    content: String,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<Vec<String>, String> {
+    let document = parse_and_migrate_export_document(
+        &content,
+        "prompt bundle",
+        PROMPT_BUNDLE_EXPORT_TYPE,
+        PROMPT_BUNDLE_MIGRATIONS,
+    )?;
+    let export: PromptBundleExportFile = serde_json::from_value(document)
+        .map_err(|e| format!("Failed to parse migrated prompt bundle file: {e}"))?;
+
+    let mut imported_names: Vec<String> = export.profiles.keys().cloned().collect();
+    imported_names.sort();
+
+    for (name, sections) in export.profiles {
+        settings_manager.set_prompt_profile(name, sections)?;
+    }
+
+    log::info!(
+        "Imported prompt bundle: {} profile(s) ({})",
+        imported_names.len(),
+        imported_names.join(", ")
+    );
+
+    Ok(imported_names)
+}
+
+/// Directory (relative to the app data directory) that pre-reset backup
+/// snapshots are written to, one subdirectory per `factory_reset` call named
+/// after its timestamp so `restore_last_backup` can find the newest one by
+/// sorting directory names.
+const BACKUP_DIR_NAME: &str = "factory-reset-backups";
+
+/// Snapshot the current settings and history into a timestamped backup
+/// directory under the app data directory, reusing the same
+/// `SettingsExportFile`/`HistoryExportFile` formats (and migration chain) the
+/// manual export/import commands use, so `restore_last_backup` can feed it
+/// straight back through `import_settings`/`import_history`. Returns the
+/// backup directory path. Best-effort: a failure here should not be allowed
+/// to block the factory reset itself, so callers log and continue on `Err`.
+fn write_pre_reset_backup(
+    app: &AppHandle,
+    settings: &AppSettings,
+    history_storage: &HistoryStorage,
+) -> Result<String, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let backup_dir = app_data_dir
+        .join(BACKUP_DIR_NAME)
+        .join(Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string());
+    std::fs::create_dir_all(&backup_dir)
+        .map_err(|e| format!("Failed to create backup directory: {e}"))?;
+
+    let settings_export = SettingsExportFile {
+        file_type: SETTINGS_EXPORT_TYPE.to_string(),
+        version: EXPORT_VERSION,
+        exported_at: Utc::now(),
+        data: settings.clone().into(),
+    };
+    std::fs::write(
+        backup_dir.join("settings.json"),
+        serde_json::to_string_pretty(&settings_export)
+            .map_err(|e| format!("Failed to serialize settings backup: {e}"))?,
+    )
+    .map_err(|e| format!("Failed to write settings backup: {e}"))?;
+
+    let entries = history_storage
+        .get_all(None)
+        .map_err(|e| format!("Failed to read history for backup: {e}"))?;
+    let history_export = HistoryExportFile {
+        file_type: HISTORY_EXPORT_TYPE.to_string(),
+        version: EXPORT_VERSION,
+        exported_at: Utc::now(),
+        count: entries.len(),
+        data: entries,
+    };
+    std::fs::write(
+        backup_dir.join("history.json"),
+        serde_json::to_string_pretty(&history_export)
+            .map_err(|e| format!("Failed to serialize history backup: {e}"))?,
+    )
+    .map_err(|e| format!("Failed to write history backup: {e}"))?;
+
+    Ok(backup_dir.to_string_lossy().into_owned())
+}
+
+/// Find the most recently written backup directory from
+/// `write_pre_reset_backup` by sorting directory names (the timestamp format
+/// sorts lexicographically in chronological order).
+fn find_last_backup_dir(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    let backups_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {e}"))?
+        .join(BACKUP_DIR_NAME);
+
+    let mut backup_names: Vec<String> = std::fs::read_dir(&backups_dir)
+        .map_err(|e| format!("No backups found: {e}"))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    backup_names.sort();
+
+    let last_name = backup_names
+        .pop()
+        .ok_or("No backups found: backup directory is empty")?;
+    Ok(backups_dir.join(last_name))
+}
+
+/// Outcome of `restore_last_backup`: the settings/history import results,
+/// plus which backup directory was restored from.
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreBackupOutcome {
+    pub backup_path: String,
+    pub settings_outcome: ImportSettingsOutcome,
+    pub history_result: HistoryImportResult,
+}
+
+/// Restore the most recent `factory_reset` backup snapshot, feeding it back
+/// through the normal `import_settings`/`import_history` paths (so it still
+/// runs through migration, like any other import) - an "undo" for an
+/// accidental factory reset, without requiring the user to have manually
+/// exported first.
+#[cfg(desktop)]
+#[tauri::command]
+pub async fn restore_last_backup(
+    app: AppHandle,
+    config_sync: tauri::State<'_, ConfigSync>,
+) -> Result<RestoreBackupOutcome, String> {
+    let backup_dir = find_last_backup_dir(&app)?;
+
+    let settings_content = std::fs::read_to_string(backup_dir.join("settings.json"))
+        .map_err(|e| format!("Failed to read settings backup: {e}"))?;
+    let history_content = std::fs::read_to_string(backup_dir.join("history.json"))
+        .map_err(|e| format!("Failed to read history backup: {e}"))?;
+
+    let settings_outcome = import_settings(app.clone(), settings_content, config_sync).await?;
+    let history_result = import_history(app, history_content, HistoryImportStrategy::Replace)?;
+
+    Ok(RestoreBackupOutcome {
+        backup_path: backup_dir.to_string_lossy().into_owned(),
+        settings_outcome,
+        history_result,
+    })
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+pub async fn restore_last_backup(
+    _app: AppHandle,
+    _config_sync: tauri::State<'_, ConfigSync>,
+) -> Result<RestoreBackupOutcome, String> {
+    Err("Not supported on this platform".to_string())
+}
+
 /// Factory reset: clears all settings and history
 #[cfg(desktop)]
 #[tauri::command]
@@ -819,35 +1367,67 @@ pub async fn factory_reset(
     app: AppHandle,
     config_sync: tauri::State<'_, ConfigSync>,
 ) -> Result<FactoryResetOutcome, String> {
-    // Clear the settings store completely
     let store = app
         .store("settings.json")
         .map_err(|e| format!("Failed to get store: {e}"))?;
 
-    store.clear();
-    store
-        .save()
-        .map_err(|e| format!("Failed to save cleared store: {e}"))?;
+    // Snapshot before clearing anything, so a failure partway through (the
+    // clear, or the re-seed) restores the store exactly as it was instead
+    // of leaving it half-cleared.
+    let snapshot = store.entries();
+    let default_settings = AppSettings::default();
 
-    // Clear history
+    // Best-effort: write a full backup of the current settings/history
+    // before touching anything, so `restore_last_backup` can undo this reset.
+    // A failed backup is logged but does not block the reset itself.
+    let current_settings = app.state::<SettingsManager>().get()?;
     let history_storage = app.state::<HistoryStorage>();
-    history_storage.clear().map_err(|error| error.to_string())?;
+    let backup_path = match write_pre_reset_backup(&app, &current_settings, &history_storage) {
+        Ok(path) => Some(path),
+        Err(error) => {
+            log::warn!("Failed to write pre-reset backup: {error}");
+            None
+        }
+    };
 
-    // Re-initialize with default settings
-    let default_settings = AppSettings::default();
+    let clear_and_reseed_result: Result<(), String> = (|| {
+        store.clear();
+        store
+            .save()
+            .map_err(|e| format!("Failed to save cleared store: {e}"))?;
+
+        write_setting_classes_to_store(
+            &default_settings,
+            &FACTORY_RESET_SETTING_CLASSES,
+            |setting_class, setting_value| {
+                store.set(setting_class.storage_key_name(), setting_value);
+            },
+        )
+        .map_err(|error| format!("Failed to serialize setting for factory reset: {error:#}"))?;
 
-    write_setting_classes_to_store(
-        &default_settings,
-        &FACTORY_RESET_SETTING_CLASSES,
-        |setting_class, setting_value| {
-            store.set(setting_class.storage_key_name(), setting_value);
-        },
-    )
-    .map_err(|error| format!("Failed to serialize setting for factory reset: {error:#}"))?;
+        store
+            .save()
+            .map_err(|e| format!("Failed to save default settings: {e}"))
+    })();
+
+    if let Err(error) = clear_and_reseed_result {
+        store.clear();
+        for (key, value) in snapshot {
+            store.set(key, value);
+        }
+        return if let Err(rollback_error) = store.save() {
+            Err(format!(
+                "{error}; rollback also failed to save ({rollback_error}) - settings store may be in a mixed state"
+            ))
+        } else {
+            Err(format!(
+                "Factory reset rolled back, settings left unchanged: {error}"
+            ))
+        };
+    }
 
-    store
-        .save()
-        .map_err(|e| format!("Failed to save default settings: {e}"))?;
+    // Clear history
+    history_storage.clear().map_err(|error| error.to_string())?;
 
     let default_sections = CleanupPromptSections::default();
     let runtime_apply_outcome = apply_runtime_side_effects(
@@ -869,7 +1449,11 @@ pub async fn factory_reset(
         );
     }
 
-    Ok(runtime_apply_outcome)
+    Ok(FactoryResetOutcome {
+        warnings: runtime_apply_outcome.warnings,
+        runtime_actions_applied: runtime_apply_outcome.runtime_actions_applied,
+        backup_path,
+    })
 }
 
 #[cfg(not(desktop))]
@@ -880,3 +1464,7 @@ pub async fn factory_reset(
 ) -> Result<FactoryResetOutcome, String> {
     Err("Not supported on this platform".to_string())
 }
+
+#[cfg(test)]
+#[path = "../tests/export_import_migration_tests.rs"]
+mod export_import_migration_tests;
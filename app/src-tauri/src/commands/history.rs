@@ -1,5 +1,5 @@
 use crate::active_app_context::ActiveAppContextSnapshot;
-use crate::history::{HistoryEntry, HistoryStorage};
+use crate::history::{HistoryEntry, HistoryQuery, HistoryStorage};
 use tauri::State;
 
 /// Add a new entry to the dictation history
@@ -24,6 +24,15 @@ pub async fn get_history(
     history.get_all(limit).map_err(|error| error.to_string())
 }
 
+/// Search dictation history by text content and/or app/origin/time-range filters
+#[tauri::command]
+pub async fn search_history(
+    query: HistoryQuery,
+    history: State<'_, HistoryStorage>,
+) -> Result<Vec<HistoryEntry>, String> {
+    history.search(query).map_err(|error| error.to_string())
+}
+
 /// Delete a history entry by ID
 #[tauri::command]
 pub async fn delete_history_entry(
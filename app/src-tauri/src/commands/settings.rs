@@ -1,4 +1,9 @@
-use crate::settings::{AppSettings, CleanupPromptSections, HotkeyConfig, SettingsManager};
+use crate::active_app_context::FocusRedactionRule;
+use crate::config_sync::ConfigSync;
+use crate::settings::{
+    AppHotkeyProfile, AppSettings, CleanupPromptSections, ContextSettingsOverride,
+    EffectiveSettings, HotkeyConfig, SettingsManager, TextInjectionMode,
+};
 use tauri::State;
 
 /// Get the current application settings
@@ -18,6 +23,26 @@ pub async fn save_settings(
     settings_manager.update(settings)
 }
 
+/// Export the settings currently in effect as a pretty-printed TOML
+/// document, for headless/enterprise/CI deployments that pin settings via a
+/// `config.toml` instead of clicking through the UI - see
+/// `settings::resolve_settings`.
+#[tauri::command]
+pub async fn export_settings_toml(
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<String, String> {
+    crate::settings::to_toml_string(&settings_manager.get()?)
+}
+
+/// Update the streaming-mode setting
+#[tauri::command]
+pub async fn update_streaming_mode(
+    enabled: bool,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), String> {
+    settings_manager.update_streaming_mode(enabled)
+}
+
 /// Update just the toggle hotkey
 #[tauri::command]
 pub async fn update_toggle_hotkey(
@@ -36,6 +61,25 @@ pub async fn update_hold_hotkey(
     settings_manager.update_hold_hotkey(hotkey)
 }
 
+/// Update just the paste-last-transcript hotkey
+#[tauri::command]
+pub async fn update_paste_last_hotkey(
+    hotkey: HotkeyConfig,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), String> {
+    settings_manager.update_paste_last_hotkey(hotkey)
+}
+
+/// Set or clear the hotkey profile override for a specific app
+#[tauri::command]
+pub async fn update_app_hotkey_profile(
+    app_identifier: String,
+    profile: Option<AppHotkeyProfile>,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), String> {
+    settings_manager.update_app_hotkey_profile(app_identifier, profile)
+}
+
 /// Update the selected microphone device
 #[tauri::command]
 pub async fn update_selected_mic(
@@ -54,6 +98,24 @@ pub async fn update_sound_enabled(
     settings_manager.update_sound_enabled(enabled)
 }
 
+/// Update the volume of sound cues
+#[tauri::command]
+pub async fn update_sound_volume(
+    volume: f32,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), String> {
+    settings_manager.update_sound_volume(volume)
+}
+
+/// Set or clear the output device sound cues are played through
+#[tauri::command]
+pub async fn update_sound_output_device(
+    device_id: Option<String>,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), String> {
+    settings_manager.update_sound_output_device(device_id)
+}
+
 /// Update the cleanup prompt sections setting
 #[tauri::command]
 pub async fn update_cleanup_prompt_sections(
@@ -63,6 +125,52 @@ pub async fn update_cleanup_prompt_sections(
     settings_manager.update_cleanup_prompt_sections(sections)
 }
 
+/// Save the currently active cleanup prompt sections as a named, reusable
+/// profile (e.g. "email", "code", "dictation")
+#[tauri::command]
+pub async fn save_prompt_profile(
+    name: String,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), String> {
+    settings_manager.save_prompt_profile(name)
+}
+
+/// List the names of saved prompt profiles, sorted alphabetically
+#[tauri::command]
+pub async fn list_prompt_profiles(
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<Vec<String>, String> {
+    settings_manager.list_prompt_profiles()
+}
+
+/// Delete a saved prompt profile by name
+#[tauri::command]
+pub async fn delete_prompt_profile(
+    name: String,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), String> {
+    settings_manager.delete_prompt_profile(&name)
+}
+
+/// Make a saved prompt profile the active cleanup prompt sections, syncing
+/// the change to the server the same way importing a single prompt does
+#[tauri::command]
+pub async fn load_prompt_profile(
+    name: String,
+    settings_manager: State<'_, SettingsManager>,
+    config_sync: State<'_, ConfigSync>,
+) -> Result<(), String> {
+    let sections = settings_manager.get_prompt_profile(&name)?;
+    settings_manager.update_cleanup_prompt_sections(Some(sections.clone()))?;
+
+    let mut sync = config_sync.write().await;
+    if let Err(e) = sync.sync_prompt_sections(&sections).await {
+        log::warn!("Failed to sync prompt sections after loading profile '{name}': {e}");
+    }
+
+    Ok(())
+}
+
 /// Update the STT provider setting
 #[tauri::command]
 pub async fn update_stt_provider(
@@ -89,3 +197,67 @@ pub async fn update_auto_mute_audio(
 ) -> Result<(), String> {
     settings_manager.update_auto_mute_audio(enabled)
 }
+
+/// Set or clear the duck level, see `AppSettings::duck_level`
+#[tauri::command]
+pub async fn update_duck_level(
+    duck_level: Option<f32>,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), String> {
+    settings_manager.update_duck_level(duck_level)
+}
+
+/// Update how transcribed text is inserted into the focused field
+#[tauri::command]
+pub async fn update_text_injection_mode(
+    mode: TextInjectionMode,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), String> {
+    settings_manager.update_text_injection_mode(mode)
+}
+
+/// Update whether the foreground app is watched for per-app hotkey profiles
+#[tauri::command]
+pub async fn update_send_active_app_context_enabled(
+    enabled: bool,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), String> {
+    settings_manager.update_send_active_app_context_enabled(enabled)
+}
+
+/// Update whether anonymous usage metrics are pushed to the server
+#[tauri::command]
+pub async fn update_telemetry_enabled(
+    enabled: bool,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), String> {
+    settings_manager.update_telemetry_enabled(enabled)
+}
+
+/// Replace the full list of focus-redaction rules
+#[tauri::command]
+pub async fn update_focus_redaction_rules(
+    rules: Vec<FocusRedactionRule>,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), String> {
+    settings_manager.update_focus_redaction_rules(rules)
+}
+
+/// Set or clear the per-origin/per-app settings override for `context_key`
+#[tauri::command]
+pub async fn set_context_override(
+    context_key: String,
+    context_override: Option<ContextSettingsOverride>,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), String> {
+    settings_manager.set_context_override(context_key, context_override)
+}
+
+/// Resolve the dictation settings actually in effect for `context_key`
+#[tauri::command]
+pub async fn get_effective_settings(
+    context_key: Option<String>,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<EffectiveSettings, String> {
+    settings_manager.get_effective_settings(context_key.as_deref())
+}
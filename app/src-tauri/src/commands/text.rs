@@ -1,12 +1,13 @@
-use arboard::Clipboard;
+use arboard::{Clipboard, ImageData};
 use enigo::{Direction, Enigo, Key, Keyboard, Settings};
 use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
-use tauri::AppHandle;
+use tauri::{AppHandle, State};
 use tauri_plugin_store::StoreExt;
 
-use crate::settings::{LocalOnlySetting, DEFAULT_SERVER_URL};
+use crate::active_app_context::{get_current_active_app_context, snapshot_blocks_autopaste};
+use crate::settings::{LocalOnlySetting, SettingsManager, TextInjectionMode, DEFAULT_SERVER_URL};
 
 /// Delay after clipboard operations to ensure system stability
 const CLIPBOARD_STABILIZATION_DELAY_MS: u64 = 50;
@@ -28,13 +29,32 @@ pub async fn get_server_url(app: AppHandle) -> Result<String, String> {
 }
 
 #[tauri::command]
-pub async fn type_text(app: AppHandle, text: String) -> Result<(), String> {
+pub async fn type_text(
+    app: AppHandle,
+    text: String,
+    settings_manager: State<'_, SettingsManager>,
+) -> Result<(), String> {
+    let text_injection_mode = settings_manager
+        .get()
+        .map(|settings| settings.text_injection_mode)
+        .unwrap_or_default();
+
     // macOS HIToolbox APIs (used by enigo) must run on the main thread
     // Use a channel to get the result back from the main thread
     let (tx, rx) = mpsc::channel::<Result<(), String>>();
 
     app.run_on_main_thread(move || {
-        let result = type_text_blocking(&text);
+        // Reading accessibility state (like `type_text_blocking`'s own
+        // keyboard events) needs the main thread, so we check it here
+        // rather than before dispatching.
+        if snapshot_blocks_autopaste(&get_current_active_app_context()) {
+            let _ = tx.send(Err(
+                "Refusing to insert text into a password/secure field".to_string()
+            ));
+            return;
+        }
+
+        let result = type_text_blocking(&text, text_injection_mode);
         let _ = tx.send(result);
     })
     .map_err(|e| e.to_string())?;
@@ -43,12 +63,48 @@ pub async fn type_text(app: AppHandle, text: String) -> Result<(), String> {
     rx.recv().map_err(|e| e.to_string())?
 }
 
-/// Type text using clipboard and paste. Used internally by shortcut handlers.
-pub fn type_text_blocking(text: &str) -> Result<(), String> {
+/// Insert `text` into the focused field using `mode`.
+pub fn type_text_blocking(text: &str, mode: TextInjectionMode) -> Result<(), String> {
+    match mode {
+        TextInjectionMode::Clipboard => type_text_via_clipboard_paste(text),
+        TextInjectionMode::Keystroke => type_text_via_keystrokes(text),
+    }
+}
+
+/// Previously-held clipboard contents, saved before a paste so they can be
+/// restored afterwards. `html` has no counterpart here because `arboard`
+/// only supports writing HTML to the clipboard, not reading it back.
+struct SavedClipboardContents {
+    text: Option<String>,
+    image: Option<ImageData<'static>>,
+}
+
+fn save_clipboard_contents(clipboard: &mut Clipboard) -> SavedClipboardContents {
+    SavedClipboardContents {
+        text: clipboard.get_text().ok(),
+        image: clipboard.get_image().ok(),
+    }
+}
+
+fn restore_clipboard_contents(clipboard: &mut Clipboard, saved: SavedClipboardContents) {
+    // Prefer restoring the image if there was one, since setting text after
+    // an image would just clobber it again; a clipboard holding both at once
+    // isn't something `arboard` models.
+    if let Some(image) = saved.image {
+        let _ = clipboard.set_image(image);
+    } else if let Some(text) = saved.text {
+        let _ = clipboard.set_text(text);
+    } else {
+        let _ = clipboard.clear();
+    }
+}
+
+/// Type text using clipboard and paste.
+fn type_text_via_clipboard_paste(text: &str) -> Result<(), String> {
     let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
 
     // Save previous clipboard content
-    let previous = clipboard.get_text().unwrap_or_default();
+    let previous = save_clipboard_contents(&mut clipboard);
 
     // Set new text
     clipboard.set_text(text).map_err(|e| e.to_string())?;
@@ -78,7 +134,21 @@ pub fn type_text_blocking(text: &str) -> Result<(), String> {
 
     // Restore previous clipboard after a delay
     thread::sleep(Duration::from_millis(CLIPBOARD_RESTORE_DELAY_MS));
-    let _ = clipboard.set_text(&previous);
+    restore_clipboard_contents(&mut clipboard, previous);
+
+    Ok(())
+}
+
+/// Type text directly through synthetic keystrokes, one character at a
+/// time, without touching the clipboard at all.
+fn type_text_via_keystrokes(text: &str) -> Result<(), String> {
+    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+
+    for character in text.chars() {
+        enigo
+            .key(Key::Unicode(character), Direction::Click)
+            .map_err(|e| e.to_string())?;
+    }
 
     Ok(())
 }
@@ -7,14 +7,108 @@ use tokio::sync::RwLock;
 
 use crate::settings::CleanupPromptSections;
 
+#[cfg(feature = "metrics")]
+use std::collections::HashMap;
+#[cfg(feature = "metrics")]
+use tauri::{AppHandle, Manager};
+
+#[cfg(feature = "metrics")]
+use crate::settings::SettingsManager;
+
 /// Default STT timeout in seconds (matches server's `DEFAULT_TRANSCRIPTION_WAIT_TIMEOUT_SECONDS`)
 pub const DEFAULT_STT_TIMEOUT_SECONDS: f64 = 0.5;
 
+/// How many times a queued sync operation is retried (with exponential
+/// backoff) before being dropped from the outbox.
+const OUTBOX_MAX_ATTEMPTS: u32 = 6;
+/// Backoff before the first retry of a queued operation; doubles (capped at
+/// `OUTBOX_MAX_BACKOFF`) for each attempt after that.
+const OUTBOX_BASE_BACKOFF: Duration = Duration::from_secs(1);
+const OUTBOX_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// A config-sync write the user made while offline, or that failed, queued
+/// for retry once connected.
+#[derive(Debug, Clone)]
+enum SyncOperation {
+    PromptSections(CleanupPromptSections),
+    SttTimeout(f64),
+    LlmFormattingEnabled(bool),
+}
+
+impl SyncOperation {
+    /// De-duplication key: enqueuing a new value for a key already pending
+    /// replaces it in place (latest value per key wins) instead of queuing
+    /// a second entry for the same setting.
+    fn key(&self) -> &'static str {
+        match self {
+            SyncOperation::PromptSections(_) => "prompt_sections",
+            SyncOperation::SttTimeout(_) => "stt_timeout",
+            SyncOperation::LlmFormattingEnabled(_) => "llm_formatting_enabled",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PendingSyncOperation {
+    operation: SyncOperation,
+    attempts: u32,
+}
+
+/// Does `error` (or something it wraps) represent a definitive 4xx
+/// response? Those are never worth retrying - the request itself is
+/// rejected, not merely undeliverable - so the outbox drops them instead
+/// of retrying with backoff.
+fn is_definitive_client_error(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<tauri_plugin_http::reqwest::Error>())
+        .and_then(|reqwest_error| reqwest_error.status())
+        .is_some_and(|status| status.is_client_error())
+}
+
+/// How often accumulated usage metrics are pushed to the server and their
+/// deltas reset.
+#[cfg(feature = "metrics")]
+const METRICS_FLUSH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Anonymous usage counters accumulated between pushes, à la a Prometheus
+/// pushgateway client: counters only ever go up between flushes, and are
+/// reset to zero once a push succeeds.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct UsageMetrics {
+    recordings_started: u64,
+    recordings_completed: u64,
+    total_recorded_audio_seconds: f64,
+    stt_provider_usage: HashMap<String, u64>,
+    llm_provider_usage: HashMap<String, u64>,
+    cleanup_sections_enabled: HashMap<String, u64>,
+    error_counts: HashMap<String, u64>,
+}
+
+#[cfg(feature = "metrics")]
+impl UsageMetrics {
+    fn is_empty(&self) -> bool {
+        self.recordings_started == 0
+            && self.recordings_completed == 0
+            && self.total_recorded_audio_seconds == 0.0
+            && self.stt_provider_usage.is_empty()
+            && self.llm_provider_usage.is_empty()
+            && self.cleanup_sections_enabled.is_empty()
+            && self.error_counts.is_empty()
+    }
+}
+
 /// Tracks server connection state for config syncing
 pub struct ConfigSyncState {
     client: Client,
     server_url: Option<String>,
     client_uuid: Option<String>,
+    /// Sync operations made while offline, or that failed, waiting to be
+    /// retried once connected (see `spawn_outbox_flush_loop`).
+    outbox: Vec<PendingSyncOperation>,
+    #[cfg(feature = "metrics")]
+    metrics: UsageMetrics,
 }
 
 impl Default for ConfigSyncState {
@@ -32,16 +126,49 @@ impl ConfigSyncState {
                 .expect("Failed to create HTTP client"),
             server_url: None,
             client_uuid: None,
+            outbox: Vec::new(),
+            #[cfg(feature = "metrics")]
+            metrics: UsageMetrics::default(),
         }
     }
 
-    /// Set connection info when connected to server
+    /// Set connection info when connected to server. Callers should follow
+    /// this with `spawn_outbox_flush_loop` to retry anything queued while
+    /// offline.
     pub fn set_connected(&mut self, server_url: String, client_uuid: String) {
         log::info!("Config sync connected: {server_url} (uuid: {client_uuid})");
         self.server_url = Some(server_url);
         self.client_uuid = Some(client_uuid);
     }
 
+    /// Number of sync operations currently queued for retry.
+    pub fn outbox_pending_count(&self) -> usize {
+        self.outbox.len()
+    }
+
+    /// Number of queued operations that have failed at least one retry.
+    pub fn outbox_failed_count(&self) -> usize {
+        self.outbox
+            .iter()
+            .filter(|pending| pending.attempts > 0)
+            .count()
+    }
+
+    fn enqueue_sync_operation(&mut self, operation: SyncOperation) {
+        let key = operation.key();
+        match self
+            .outbox
+            .iter_mut()
+            .find(|pending| pending.operation.key() == key)
+        {
+            Some(existing) => existing.operation = operation,
+            None => self.outbox.push(PendingSyncOperation {
+                operation,
+                attempts: 0,
+            }),
+        }
+    }
+
     /// Clear connection info when disconnected
     pub fn set_disconnected(&mut self) {
         self.server_url = None;
@@ -54,94 +181,334 @@ impl ConfigSyncState {
         self.server_url.is_some() && self.client_uuid.is_some()
     }
 
-    /// Sync prompt sections to server (best-effort, logs errors)
-    pub async fn sync_prompt_sections(&self, sections: &CleanupPromptSections) -> Result<()> {
+    /// The HTTP client used for all sync/metrics/streaming requests.
+    pub fn client(&self) -> Client {
+        self.client.clone()
+    }
+
+    /// The connected server's base URL, if any.
+    pub fn server_url(&self) -> Option<&str> {
+        self.server_url.as_deref()
+    }
+
+    /// The connected client's pairing UUID, if any.
+    pub fn client_uuid(&self) -> Option<&str> {
+        self.client_uuid.as_deref()
+    }
+
+    /// Send a single sync operation to the server. Callers are expected to
+    /// have already checked `is_connected`.
+    async fn perform_operation(&self, operation: &SyncOperation) -> Result<()> {
         let (Some(server_url), Some(client_uuid)) = (&self.server_url, &self.client_uuid) else {
-            return Ok(()); // Not connected, skip silently
+            anyhow::bail!("Not connected to a server");
         };
 
-        let endpoint_url = format!("{server_url}/api/config/prompts");
-        self.client
-            .put(&endpoint_url)
-            .header("X-Client-UUID", client_uuid)
-            .json(sections)
-            .send()
-            .await
-            .with_context(|| {
-                format!("Failed to send prompt sections sync request to {endpoint_url}")
-            })?
-            .error_for_status()
-            .with_context(|| {
-                format!(
-                    "Server returned an error for prompt sections sync request to {endpoint_url}"
-                )
-            })?;
+        match operation {
+            SyncOperation::PromptSections(sections) => {
+                let endpoint_url = format!("{server_url}/api/config/prompts");
+                self.client
+                    .put(&endpoint_url)
+                    .header("X-Client-UUID", client_uuid)
+                    .json(sections)
+                    .send()
+                    .await
+                    .with_context(|| {
+                        format!("Failed to send prompt sections sync request to {endpoint_url}")
+                    })?
+                    .error_for_status()
+                    .with_context(|| {
+                        format!(
+                            "Server returned an error for prompt sections sync request to {endpoint_url}"
+                        )
+                    })?;
+                log::debug!("Synced prompt sections to server");
+            }
+            SyncOperation::SttTimeout(timeout_seconds) => {
+                #[derive(Serialize)]
+                struct TimeoutBody {
+                    timeout_seconds: f64,
+                }
+
+                let endpoint_url = format!("{server_url}/api/config/stt-timeout");
+                self.client
+                    .put(&endpoint_url)
+                    .header("X-Client-UUID", client_uuid)
+                    .json(&TimeoutBody {
+                        timeout_seconds: *timeout_seconds,
+                    })
+                    .send()
+                    .await
+                    .with_context(|| {
+                        format!("Failed to send STT timeout sync request to {endpoint_url}")
+                    })?
+                    .error_for_status()
+                    .with_context(|| {
+                        format!(
+                            "Server returned an error for STT timeout sync request to {endpoint_url}"
+                        )
+                    })?;
+                log::debug!("Synced STT timeout ({timeout_seconds}) to server");
+            }
+            SyncOperation::LlmFormattingEnabled(enabled) => {
+                #[derive(Serialize)]
+                struct LlmFormattingBody {
+                    enabled: bool,
+                }
+
+                let endpoint_url = format!("{server_url}/api/config/llm-formatting");
+                self.client
+                    .put(&endpoint_url)
+                    .header("X-Client-UUID", client_uuid)
+                    .json(&LlmFormattingBody { enabled: *enabled })
+                    .send()
+                    .await
+                    .with_context(|| {
+                        format!("Failed to send LLM formatting sync request to {endpoint_url}")
+                    })?
+                    .error_for_status()
+                    .with_context(|| {
+                        format!(
+                            "Server returned an error for LLM formatting sync request to {endpoint_url}"
+                        )
+                    })?;
+                log::debug!("Synced LLM formatting enabled={enabled} to server");
+            }
+        }
 
-        log::debug!("Synced prompt sections to server");
         Ok(())
     }
 
-    /// Sync STT timeout to server
-    pub async fn sync_stt_timeout(&self, timeout_seconds: f64) -> Result<()> {
-        #[derive(Serialize)]
-        struct TimeoutBody {
-            timeout_seconds: f64,
+    /// Attempt `operation` immediately if connected, otherwise queue it.
+    /// A failed immediate attempt is also queued for retry, unless the
+    /// server rejected it outright (a definitive 4xx), in which case it's
+    /// dropped instead since retrying wouldn't help.
+    async fn sync_or_enqueue(&mut self, operation: SyncOperation) -> Result<()> {
+        if !self.is_connected() {
+            self.enqueue_sync_operation(operation);
+            return Ok(());
         }
 
-        let (Some(server_url), Some(client_uuid)) = (&self.server_url, &self.client_uuid) else {
-            return Ok(()); // Not connected, skip silently
-        };
+        match self.perform_operation(&operation).await {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                if is_definitive_client_error(&e) {
+                    log::warn!(
+                        "Sync operation '{}' rejected by server, dropping: {e}",
+                        operation.key()
+                    );
+                } else {
+                    self.enqueue_sync_operation(operation);
+                }
+                Err(e)
+            }
+        }
+    }
 
-        let endpoint_url = format!("{server_url}/api/config/stt-timeout");
-        self.client
-            .put(&endpoint_url)
-            .header("X-Client-UUID", client_uuid)
-            .json(&TimeoutBody { timeout_seconds })
-            .send()
+    /// Sync prompt sections to server, queueing on failure or while offline.
+    pub async fn sync_prompt_sections(&mut self, sections: &CleanupPromptSections) -> Result<()> {
+        self.sync_or_enqueue(SyncOperation::PromptSections(sections.clone()))
             .await
-            .with_context(|| format!("Failed to send STT timeout sync request to {endpoint_url}"))?
-            .error_for_status()
-            .with_context(|| {
-                format!("Server returned an error for STT timeout sync request to {endpoint_url}")
-            })?;
+    }
 
-        log::debug!("Synced STT timeout ({timeout_seconds}) to server");
-        Ok(())
+    /// Sync STT timeout to server, queueing on failure or while offline.
+    pub async fn sync_stt_timeout(&mut self, timeout_seconds: f64) -> Result<()> {
+        self.sync_or_enqueue(SyncOperation::SttTimeout(timeout_seconds))
+            .await
+    }
+
+    /// Sync LLM formatting enabled setting to server, queueing on failure
+    /// or while offline.
+    pub async fn sync_llm_formatting_enabled(&mut self, enabled: bool) -> Result<()> {
+        self.sync_or_enqueue(SyncOperation::LlmFormattingEnabled(enabled))
+            .await
+    }
+
+    /// Record that a recording was started, for the next usage-metrics push.
+    #[cfg(feature = "metrics")]
+    pub fn record_recording_started(&mut self) {
+        self.metrics.recordings_started += 1;
     }
 
-    /// Sync LLM formatting enabled setting to server
-    pub async fn sync_llm_formatting_enabled(&self, enabled: bool) -> Result<()> {
-        #[derive(Serialize)]
-        struct LlmFormattingBody {
-            enabled: bool,
+    /// Record that a recording finished, attributing its duration and the
+    /// providers used to the next usage-metrics push.
+    #[cfg(feature = "metrics")]
+    pub fn record_recording_completed(
+        &mut self,
+        duration_seconds: f64,
+        stt_provider: &str,
+        llm_provider: Option<&str>,
+    ) {
+        self.metrics.recordings_completed += 1;
+        self.metrics.total_recorded_audio_seconds += duration_seconds;
+        *self
+            .metrics
+            .stt_provider_usage
+            .entry(stt_provider.to_string())
+            .or_insert(0) += 1;
+        if let Some(llm_provider) = llm_provider {
+            *self
+                .metrics
+                .llm_provider_usage
+                .entry(llm_provider.to_string())
+                .or_insert(0) += 1;
         }
+    }
+
+    /// Record which cleanup sections were enabled for a completed recording.
+    #[cfg(feature = "metrics")]
+    pub fn record_cleanup_sections_enabled(&mut self, sections: &CleanupPromptSections) {
+        let named_sections = [
+            ("main", sections.main.enabled),
+            ("advanced", sections.advanced.enabled),
+            ("dictionary", sections.dictionary.enabled),
+        ];
+        for (name, enabled) in named_sections {
+            if enabled {
+                *self
+                    .metrics
+                    .cleanup_sections_enabled
+                    .entry(name.to_string())
+                    .or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// Record an error, bucketed by `error_kind` (e.g. "stt", "llm", "audio").
+    #[cfg(feature = "metrics")]
+    pub fn record_error(&mut self, error_kind: &str) {
+        *self
+            .metrics
+            .error_counts
+            .entry(error_kind.to_string())
+            .or_insert(0) += 1;
+    }
 
+    /// Push accumulated usage metrics to the server and reset the deltas,
+    /// but only if there's anything to report. Skips silently when not
+    /// connected, keeping the deltas for the next push; unlike the `sync_*`
+    /// methods, nothing is queued in the outbox.
+    #[cfg(feature = "metrics")]
+    pub async fn push_metrics(&mut self) -> Result<()> {
         let (Some(server_url), Some(client_uuid)) = (&self.server_url, &self.client_uuid) else {
             return Ok(()); // Not connected, skip silently
         };
 
-        let endpoint_url = format!("{server_url}/api/config/llm-formatting");
+        if self.metrics.is_empty() {
+            return Ok(());
+        }
+
+        let endpoint_url = format!("{server_url}/api/metrics");
         self.client
             .put(&endpoint_url)
             .header("X-Client-UUID", client_uuid)
-            .json(&LlmFormattingBody { enabled })
+            .json(&self.metrics)
             .send()
             .await
-            .with_context(|| {
-                format!("Failed to send LLM formatting sync request to {endpoint_url}")
-            })?
+            .with_context(|| format!("Failed to send usage metrics push to {endpoint_url}"))?
             .error_for_status()
             .with_context(|| {
-                format!(
-                    "Server returned an error for LLM formatting sync request to {endpoint_url}"
-                )
+                format!("Server returned an error for usage metrics push to {endpoint_url}")
             })?;
 
-        log::debug!("Synced LLM formatting enabled={enabled} to server");
+        self.metrics = UsageMetrics::default();
+        log::debug!("Pushed usage metrics to server");
         Ok(())
     }
 }
 
+/// Spawn a background task that pushes accumulated usage metrics every
+/// `METRICS_FLUSH_INTERVAL`, as long as `telemetry_enabled` is on. Intended
+/// to be called once from `setup()`.
+#[cfg(feature = "metrics")]
+pub fn spawn_metrics_flush_loop(app_handle: AppHandle, config_sync: ConfigSync) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(METRICS_FLUSH_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let telemetry_enabled = app_handle
+                .try_state::<SettingsManager>()
+                .map(|settings_manager| settings_manager.get().map(|s| s.telemetry_enabled))
+                .transpose()
+                .ok()
+                .flatten()
+                .unwrap_or(false);
+            if !telemetry_enabled {
+                continue;
+            }
+
+            let mut config_sync = config_sync.write().await;
+            if let Err(e) = config_sync.push_metrics().await {
+                log::warn!("Failed to push usage metrics: {e}");
+            }
+        }
+    });
+}
+
+/// Retry everything in the outbox in order, with exponential backoff
+/// (capped at `OUTBOX_MAX_BACKOFF`) between attempts on the same operation,
+/// up to `OUTBOX_MAX_ATTEMPTS` before giving up on it. Stops once the
+/// outbox is empty or we're no longer connected (e.g. the user
+/// disconnected again mid-flush). Call this once after every successful
+/// `set_connected`.
+pub fn spawn_outbox_flush_loop(config_sync: ConfigSync) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let pending = {
+                let sync = config_sync.read().await;
+                if !sync.is_connected() {
+                    return;
+                }
+                let Some(pending) = sync.outbox.first().cloned() else {
+                    return;
+                };
+                pending
+            };
+
+            let result = {
+                let sync = config_sync.read().await;
+                sync.perform_operation(&pending.operation).await
+            };
+
+            match result {
+                Ok(()) => {
+                    let mut sync = config_sync.write().await;
+                    sync.outbox
+                        .retain(|p| p.operation.key() != pending.operation.key());
+                }
+                Err(e) => {
+                    let attempts = pending.attempts + 1;
+                    let give_up = is_definitive_client_error(&e) || attempts >= OUTBOX_MAX_ATTEMPTS;
+
+                    let mut sync = config_sync.write().await;
+                    if give_up {
+                        log::warn!(
+                            "Giving up on queued sync '{}' after {attempts} attempt(s): {e}",
+                            pending.operation.key()
+                        );
+                        sync.outbox
+                            .retain(|p| p.operation.key() != pending.operation.key());
+                    } else if let Some(slot) = sync
+                        .outbox
+                        .iter_mut()
+                        .find(|p| p.operation.key() == pending.operation.key())
+                    {
+                        slot.attempts = attempts;
+                    }
+                    drop(sync);
+
+                    if !give_up {
+                        let backoff = OUTBOX_BASE_BACKOFF
+                            .saturating_mul(1 << attempts.saturating_sub(1).min(8))
+                            .min(OUTBOX_MAX_BACKOFF);
+                        tokio::time::sleep(backoff).await;
+                    }
+                }
+            }
+        }
+    });
+}
+
 pub type ConfigSync = Arc<RwLock<ConfigSyncState>>;
 
 pub fn new_config_sync() -> ConfigSync {
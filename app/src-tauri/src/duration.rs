@@ -0,0 +1,208 @@
+//! Human-readable duration strings (`"30s"`, `"1m30s"`, `"500ms"`), for
+//! config/export fields that would otherwise be a bare seconds count - see
+//! atuin's `HISTORY_TIMEOUT`/`parse_duration` for the prior art. Parsing
+//! always produces seconds as `f64`; a plain number is still accepted so
+//! older exports keep loading.
+
+use std::fmt;
+
+/// Smallest duration this parser will accept, to keep a typo like `"0s"`
+/// from silently disabling a timeout outright.
+const MIN_SECONDS: f64 = 0.001;
+
+/// Parse a duration string made of one or more `<number><unit>` components
+/// (`ms`, `s`, `m`, `h`), e.g. `"1m30s"`, and sum them to a seconds count.
+/// Components may not repeat a unit or appear out of `h > m > s > ms` order.
+pub fn parse_duration_seconds(input: &str) -> Result<f64, DurationParseError> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err(DurationParseError::Empty);
+    }
+
+    let mut total_seconds = 0.0;
+    let mut remaining = trimmed;
+    let mut last_unit_rank = None;
+
+    while !remaining.is_empty() {
+        let digits_end = remaining
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .ok_or_else(|| DurationParseError::MissingUnit(trimmed.to_string()))?;
+        if digits_end == 0 {
+            return Err(DurationParseError::InvalidComponent(trimmed.to_string()));
+        }
+        let (number_part, rest) = remaining.split_at(digits_end);
+        let number: f64 = number_part
+            .parse()
+            .map_err(|_| DurationParseError::InvalidComponent(trimmed.to_string()))?;
+
+        let unit_end = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (unit, rest) = rest.split_at(unit_end);
+        let (unit_seconds, unit_rank) = match unit {
+            "ms" => (number / 1000.0, 0),
+            "s" => (number, 1),
+            "m" => (number * 60.0, 2),
+            "h" => (number * 3600.0, 3),
+            other => return Err(DurationParseError::UnknownUnit(other.to_string())),
+        };
+        if last_unit_rank.is_some_and(|last_rank| unit_rank >= last_rank) {
+            return Err(DurationParseError::OutOfOrder(trimmed.to_string()));
+        }
+        last_unit_rank = Some(unit_rank);
+
+        total_seconds += unit_seconds;
+        remaining = rest;
+    }
+
+    Ok(total_seconds.max(MIN_SECONDS))
+}
+
+/// Render a seconds count back to its canonical human-readable form, e.g.
+/// `90.0` -> `"1m30s"`, `1.5` -> `"1s500ms"`. Rounded to the millisecond,
+/// and always round-trips through `parse_duration_seconds` at that precision.
+pub fn format_duration_seconds(total_seconds: f64) -> String {
+    let total_milliseconds = (total_seconds * 1000.0).round() as u64;
+    let hours = total_milliseconds / 3_600_000;
+    let minutes = total_milliseconds / 60_000 % 60;
+    let seconds = total_milliseconds / 1000 % 60;
+    let milliseconds = total_milliseconds % 1000;
+
+    let mut rendered = String::new();
+    if hours > 0 {
+        rendered.push_str(&format!("{hours}h"));
+    }
+    if minutes > 0 {
+        rendered.push_str(&format!("{minutes}m"));
+    }
+    if seconds > 0 {
+        rendered.push_str(&format!("{seconds}s"));
+    }
+    if milliseconds > 0 || rendered.is_empty() {
+        rendered.push_str(&format!("{milliseconds}ms"));
+    }
+    rendered
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DurationParseError {
+    Empty,
+    InvalidComponent(String),
+    MissingUnit(String),
+    UnknownUnit(String),
+    OutOfOrder(String),
+}
+
+impl fmt::Display for DurationParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "duration string is empty"),
+            Self::InvalidComponent(input) => write!(f, "invalid duration \"{input}\""),
+            Self::MissingUnit(input) => {
+                write!(f, "duration \"{input}\" is missing a unit (ms, s, m, h)")
+            }
+            Self::UnknownUnit(unit) => write!(f, "unknown duration unit \"{unit}\""),
+            Self::OutOfOrder(input) => write!(
+                f,
+                "duration \"{input}\" must use units in h, m, s, ms order, each at most once"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DurationParseError {}
+
+/// A `serde(with = "duration::seconds_option")` helper for `Option<f64>`
+/// fields that should accept either a human-readable duration string (the
+/// canonical form, also used when serializing) or a plain number of seconds
+/// (kept for backward compatibility with older exports).
+pub mod seconds_option {
+    use super::{format_duration_seconds, parse_duration_seconds};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(seconds) => serializer.serialize_some(&format_duration_seconds(*seconds)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum SecondsOrDuration {
+            Seconds(f64),
+            Duration(String),
+        }
+
+        match Option::<SecondsOrDuration>::deserialize(deserializer)? {
+            None => Ok(None),
+            Some(SecondsOrDuration::Seconds(seconds)) => Ok(Some(seconds.max(super::MIN_SECONDS))),
+            Some(SecondsOrDuration::Duration(duration)) => parse_duration_seconds(&duration)
+                .map(Some)
+                .map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_single_unit() {
+        assert_eq!(parse_duration_seconds("30s"), Ok(30.0));
+        assert_eq!(parse_duration_seconds("500ms"), Ok(0.5));
+    }
+
+    #[test]
+    fn parses_combined_units() {
+        assert_eq!(parse_duration_seconds("1m30s"), Ok(90.0));
+        assert_eq!(parse_duration_seconds("1h30m"), Ok(5400.0));
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        assert_eq!(parse_duration_seconds(""), Err(DurationParseError::Empty));
+        assert_eq!(
+            parse_duration_seconds("   "),
+            Err(DurationParseError::Empty)
+        );
+    }
+
+    #[test]
+    fn rejects_garbage_input() {
+        assert!(parse_duration_seconds("abc").is_err());
+        assert!(parse_duration_seconds("30").is_err());
+        assert!(parse_duration_seconds("30x").is_err());
+    }
+
+    #[test]
+    fn rejects_out_of_order_or_repeated_units() {
+        assert!(parse_duration_seconds("30s1m").is_err());
+        assert!(parse_duration_seconds("30s30s").is_err());
+    }
+
+    #[test]
+    fn clamps_to_the_minimum() {
+        assert_eq!(parse_duration_seconds("0s"), Ok(MIN_SECONDS));
+    }
+
+    #[test]
+    fn formats_round_trip_through_parsing() {
+        for seconds in [0.5, 1.0, 30.0, 90.0, 5400.0, 3661.0] {
+            let formatted = format_duration_seconds(seconds);
+            let reparsed = parse_duration_seconds(&formatted).expect("should reparse");
+            assert!(
+                (reparsed - seconds).abs() < 0.001,
+                "{formatted} -> {reparsed}"
+            );
+        }
+    }
+}
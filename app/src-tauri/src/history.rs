@@ -1,5 +1,7 @@
 use crate::active_app_context::ActiveAppContextSnapshot;
 use anyhow::{Context, Result};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -7,12 +9,24 @@ use std::fs;
 use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::RwLock;
 use tempfile::NamedTempFile;
 use uuid::Uuid;
 
 const MAX_HISTORY_ENTRIES: usize = 500;
 
+/// Number of append-only log records to accumulate before compacting them
+/// into the `history.json` snapshot and truncating `history.log`.
+const LOG_COMPACTION_THRESHOLD: usize = 200;
+
+/// Magic bytes identifying an encrypted `history.json` snapshot. Legacy
+/// plaintext files never start with this, so detection is unambiguous.
+const HISTORY_ENCRYPTION_MAGIC: &[u8; 8] = b"TMBRHS01";
+/// Header format version, bumped if the encryption scheme ever changes.
+const HISTORY_ENCRYPTION_VERSION: u8 = 1;
+const HISTORY_ENCRYPTION_NONCE_LEN: usize = 24;
+
 /// Strategy for importing history entries
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -23,6 +37,11 @@ pub enum HistoryImportStrategy {
     MergeAppend,
     /// Merge but skip entries with matching IDs
     MergeDeduplicate,
+    /// Merge but skip entries with a matching content fingerprint (text,
+    /// raw text, and timestamp truncated to the second), regardless of ID.
+    /// Useful for re-importing an export from another machine, where the
+    /// same dictation gets a fresh UUID each time.
+    MergeDeduplicateByContent,
 }
 
 /// Result of a history import operation
@@ -33,6 +52,18 @@ pub struct HistoryImportResult {
     pub entries_skipped: Option<usize>,
 }
 
+/// Output format for `HistoryStorage::export_entries`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryExportFormat {
+    /// Plain JSON array of entries
+    Json,
+    /// Comma-separated values, one row per entry
+    Csv,
+    /// Markdown sections, one per entry, suitable for pasting into notes
+    Markdown,
+}
+
 /// A single dictation history entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
@@ -67,22 +98,142 @@ struct HistoryData {
     entries: Vec<HistoryEntry>,
 }
 
-/// Manages loading and saving of dictation history
+/// A single mutation appended to `history.log`. Replayed in order on top of
+/// the last compacted `history.json` snapshot to reconstruct current state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum HistoryLogOperation {
+    Add { entry: HistoryEntry },
+    Delete { id: String },
+    Clear,
+}
+
+/// Criteria for `HistoryStorage::search`. All filters are ANDed together;
+/// leaving a field `None` skips that filter entirely.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryQuery {
+    /// Case-insensitive substring matched against `text`, `raw_text`, the
+    /// focused window title, the focused app's display name, and the
+    /// browser origin.
+    #[serde(default)]
+    pub term: Option<String>,
+    /// Restrict to entries dictated into this app (matched against
+    /// `FocusedApplication::bundle_id`).
+    #[serde(default)]
+    pub app_bundle_id: Option<String>,
+    /// Restrict to entries dictated into this browser origin (matched
+    /// against `FocusedBrowserTab::origin`).
+    #[serde(default)]
+    pub origin: Option<String>,
+    /// Only entries with `timestamp >= from`.
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    /// Only entries with `timestamp <= to`.
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    /// Maximum number of matching entries to return.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl HistoryQuery {
+    fn matches(&self, entry: &HistoryEntry) -> bool {
+        if let Some(from) = self.from {
+            if entry.timestamp < from {
+                return false;
+            }
+        }
+        if let Some(to) = self.to {
+            if entry.timestamp > to {
+                return false;
+            }
+        }
+
+        let app_context = entry.active_app_context.as_ref();
+
+        if let Some(app_bundle_id) = &self.app_bundle_id {
+            let entry_bundle_id = app_context
+                .and_then(|context| context.focused_application.as_ref())
+                .and_then(|application| application.bundle_id.as_deref());
+            if entry_bundle_id != Some(app_bundle_id.as_str()) {
+                return false;
+            }
+        }
+
+        if let Some(origin) = &self.origin {
+            let entry_origin = app_context
+                .and_then(|context| context.focused_browser_tab.as_ref())
+                .and_then(|tab| tab.origin.as_deref());
+            if entry_origin != Some(origin.as_str()) {
+                return false;
+            }
+        }
+
+        if let Some(term) = &self.term {
+            let term = term.to_lowercase();
+            let haystacks = [
+                Some(entry.text.as_str()),
+                Some(entry.raw_text.as_str()),
+                app_context
+                    .and_then(|context| context.focused_window.as_ref())
+                    .map(|window| window.title.as_str()),
+                app_context
+                    .and_then(|context| context.focused_application.as_ref())
+                    .map(|application| application.display_name.as_str()),
+                app_context
+                    .and_then(|context| context.focused_browser_tab.as_ref())
+                    .and_then(|tab| tab.origin.as_deref()),
+            ];
+            if !haystacks
+                .into_iter()
+                .flatten()
+                .any(|haystack| haystack.to_lowercase().contains(&term))
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Manages loading and saving of dictation history.
+///
+/// Current state lives in the in-memory `data` lock. It is backed by two
+/// files: a compacted `history.json` snapshot, and an append-only
+/// `history.log` of mutations made since that snapshot was written. Mutating
+/// methods append a single log record instead of rewriting the whole
+/// snapshot, so `add_entry`/`delete`/`clear` are O(1) disk work rather than
+/// O(entries). Once the log grows past `LOG_COMPACTION_THRESHOLD` records it
+/// is folded back into a fresh snapshot and truncated.
+///
+/// When constructed with an `encryption_key`, the `history.json` snapshot is
+/// encrypted at rest with XChaCha20-Poly1305 behind a versioned header.
+/// Legacy plaintext snapshots are still read transparently. Note that
+/// `history.log` is not yet covered by this — it's a known gap, since the
+/// journal is meant to be folded into an encrypted snapshot promptly via
+/// compaction rather than accumulate indefinitely.
 pub struct HistoryStorage {
     data: RwLock<HistoryData>,
     file_path: PathBuf,
+    log_file_path: PathBuf,
+    log_record_count: AtomicUsize,
+    encryption_key: Option<[u8; 32]>,
 }
 
 impl HistoryStorage {
-    /// Create a new history storage with the given app data directory
-    pub fn new(app_data_dir: PathBuf) -> Self {
+    /// Create a new history storage with the given app data directory. If
+    /// `encryption_key` is `Some`, the `history.json` snapshot is encrypted
+    /// at rest; existing plaintext snapshots are still read transparently.
+    pub fn new(app_data_dir: PathBuf, encryption_key: Option<[u8; 32]>) -> Self {
         let file_path = app_data_dir.join("history.json");
+        let log_file_path = app_data_dir.join("history.log");
 
         if let Some(parent) = file_path.parent() {
             let _ = fs::create_dir_all(parent);
         }
 
-        let data = match Self::load_from_file(&file_path) {
+        let mut data = match Self::load_from_file(&file_path, encryption_key.as_ref()) {
             Ok(history_data) => history_data,
             Err(error) => {
                 if file_path.exists() {
@@ -95,21 +246,137 @@ impl HistoryStorage {
             }
         };
 
+        let log_record_count = Self::replay_log(&log_file_path, &mut data);
+
         Self {
             data: RwLock::new(data),
             file_path,
+            log_file_path,
+            log_record_count: AtomicUsize::new(log_record_count),
+            encryption_key,
         }
     }
 
-    /// Load history from the JSON file
-    fn load_from_file(file_path: &Path) -> Result<HistoryData> {
-        let file_content = fs::read_to_string(file_path)
+    /// Load history from the JSON file, transparently decrypting it first if
+    /// it carries the encrypted-snapshot header.
+    fn load_from_file(file_path: &Path, encryption_key: Option<&[u8; 32]>) -> Result<HistoryData> {
+        let file_bytes = fs::read(file_path)
             .with_context(|| format!("Failed to read history file {}", file_path.display()))?;
 
-        serde_json::from_str(&file_content)
+        let json_bytes = if file_bytes.starts_with(HISTORY_ENCRYPTION_MAGIC) {
+            let key = encryption_key.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "History file {} is encrypted but no encryption key was provided",
+                    file_path.display()
+                )
+            })?;
+            Self::decrypt_history_snapshot(&file_bytes, key).with_context(|| {
+                format!("Failed to decrypt history file {}", file_path.display())
+            })?
+        } else {
+            file_bytes
+        };
+
+        serde_json::from_slice(&json_bytes)
             .with_context(|| format!("Failed to parse history file {}", file_path.display()))
     }
 
+    /// Encrypt serialized history JSON into a versioned, nonce-prefixed blob
+    fn encrypt_history_snapshot(
+        serialized_history_content: &[u8],
+        key: &[u8; 32],
+    ) -> Result<Vec<u8>> {
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let ciphertext = cipher
+            .encrypt(&nonce, serialized_history_content)
+            .map_err(|_| anyhow::anyhow!("Failed to encrypt history data"))?;
+
+        let mut encrypted_content =
+            Vec::with_capacity(HISTORY_ENCRYPTION_MAGIC.len() + 1 + nonce.len() + ciphertext.len());
+        encrypted_content.extend_from_slice(HISTORY_ENCRYPTION_MAGIC);
+        encrypted_content.push(HISTORY_ENCRYPTION_VERSION);
+        encrypted_content.extend_from_slice(&nonce);
+        encrypted_content.extend_from_slice(&ciphertext);
+
+        Ok(encrypted_content)
+    }
+
+    /// Decrypt a versioned, nonce-prefixed blob produced by `encrypt_history_snapshot`
+    fn decrypt_history_snapshot(file_bytes: &[u8], key: &[u8; 32]) -> Result<Vec<u8>> {
+        let header_len = HISTORY_ENCRYPTION_MAGIC.len() + 1 + HISTORY_ENCRYPTION_NONCE_LEN;
+        if file_bytes.len() < header_len {
+            anyhow::bail!("Encrypted history file is truncated");
+        }
+
+        let version = file_bytes[HISTORY_ENCRYPTION_MAGIC.len()];
+        if version != HISTORY_ENCRYPTION_VERSION {
+            anyhow::bail!("Unsupported history encryption version {version}");
+        }
+
+        let nonce_start = HISTORY_ENCRYPTION_MAGIC.len() + 1;
+        let nonce_end = nonce_start + HISTORY_ENCRYPTION_NONCE_LEN;
+        let nonce = XNonce::from_slice(&file_bytes[nonce_start..nonce_end]);
+        let ciphertext = &file_bytes[nonce_end..];
+
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+        cipher.decrypt(nonce, ciphertext).map_err(|_| {
+            anyhow::anyhow!("Failed to decrypt history file: wrong key or corrupted data")
+        })
+    }
+
+    /// Replay the append-only log on top of an already-loaded snapshot,
+    /// returning the number of records applied. Lines that fail to parse
+    /// (e.g. a partial line left by a crash mid-write) are skipped rather
+    /// than aborting the whole replay.
+    fn replay_log(log_file_path: &Path, history_data: &mut HistoryData) -> usize {
+        let log_content = match fs::read_to_string(log_file_path) {
+            Ok(content) => content,
+            Err(_) => return 0,
+        };
+
+        let mut applied_record_count = 0;
+        for log_line in log_content.lines() {
+            if log_line.trim().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str::<HistoryLogOperation>(log_line) {
+                Ok(operation) => {
+                    Self::apply_log_operation(history_data, operation);
+                    applied_record_count += 1;
+                }
+                Err(error) => {
+                    log::warn!(
+                        "Skipping unreadable history log record in {}: {error}",
+                        log_file_path.display()
+                    );
+                }
+            }
+        }
+
+        applied_record_count
+    }
+
+    /// Apply a single log operation to in-memory history data
+    fn apply_log_operation(history_data: &mut HistoryData, operation: HistoryLogOperation) {
+        match operation {
+            HistoryLogOperation::Add { entry } => {
+                history_data.entries.insert(0, entry);
+                if history_data.entries.len() > MAX_HISTORY_ENTRIES {
+                    history_data.entries.truncate(MAX_HISTORY_ENTRIES);
+                }
+            }
+            HistoryLogOperation::Delete { id } => {
+                history_data.entries.retain(|entry| entry.id != id);
+            }
+            HistoryLogOperation::Clear => {
+                history_data.entries.clear();
+            }
+        }
+    }
+
     /// Save current history to disk
     fn save(&self) -> Result<()> {
         let history_data = self.data.read().map_err(|error| {
@@ -119,6 +386,12 @@ impl HistoryStorage {
         let serialized_history_content = serde_json::to_string_pretty(&*history_data)
             .context("Failed to serialize history data to JSON")?;
 
+        let file_content = match &self.encryption_key {
+            Some(key) => Self::encrypt_history_snapshot(serialized_history_content.as_bytes(), key)
+                .context("Failed to encrypt history data")?,
+            None => serialized_history_content.into_bytes(),
+        };
+
         let history_directory_path = self
             .file_path
             .parent()
@@ -133,7 +406,7 @@ impl HistoryStorage {
             })?;
 
         temporary_history_file
-            .write_all(serialized_history_content.as_bytes())
+            .write_all(&file_content)
             .with_context(|| {
                 format!(
                     "Failed to write temporary history file for {}",
@@ -171,6 +444,74 @@ impl HistoryStorage {
         Ok(())
     }
 
+    /// Append a single mutation to `history.log`, compacting into a fresh
+    /// snapshot once the log has accumulated `LOG_COMPACTION_THRESHOLD` records
+    fn append_log_record(&self, operation: &HistoryLogOperation) -> Result<()> {
+        let serialized_operation = serde_json::to_string(operation)
+            .context("Failed to serialize history log operation")?;
+
+        let mut log_file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_file_path)
+            .with_context(|| {
+                format!(
+                    "Failed to open history log file {}",
+                    self.log_file_path.display()
+                )
+            })?;
+
+        writeln!(log_file, "{serialized_operation}").with_context(|| {
+            format!(
+                "Failed to append to history log file {}",
+                self.log_file_path.display()
+            )
+        })?;
+
+        log_file.sync_all().with_context(|| {
+            format!(
+                "Failed to sync history log file {}",
+                self.log_file_path.display()
+            )
+        })?;
+
+        let pending_record_count = self.log_record_count.fetch_add(1, Ordering::SeqCst) + 1;
+        if pending_record_count >= LOG_COMPACTION_THRESHOLD {
+            self.compact()?;
+        }
+
+        Ok(())
+    }
+
+    /// Fold the append-only log back into a fresh `history.json` snapshot and
+    /// truncate `history.log`
+    fn compact(&self) -> Result<()> {
+        self.save()?;
+
+        let truncated_log_file = fs::OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(&self.log_file_path)
+            .with_context(|| {
+                format!(
+                    "Failed to truncate history log file {}",
+                    self.log_file_path.display()
+                )
+            })?;
+
+        truncated_log_file.sync_all().with_context(|| {
+            format!(
+                "Failed to sync truncated history log file {}",
+                self.log_file_path.display()
+            )
+        })?;
+
+        self.log_record_count.store(0, Ordering::SeqCst);
+
+        Ok(())
+    }
+
     /// Add a new entry to the history
     pub fn add_entry(
         &self,
@@ -190,7 +531,9 @@ impl HistoryStorage {
                 history_data.entries.truncate(MAX_HISTORY_ENTRIES);
             }
         }
-        self.save()?;
+        self.append_log_record(&HistoryLogOperation::Add {
+            entry: new_history_entry.clone(),
+        })?;
         Ok(new_history_entry)
     }
 
@@ -213,6 +556,29 @@ impl HistoryStorage {
         Ok(history_entries)
     }
 
+    /// Search entries by substring/app/origin/time-range criteria, newest-first.
+    ///
+    /// Unlike `get_all`, this filters before truncating to `limit`, so the
+    /// caller gets the `limit` most recent *matching* entries rather than
+    /// having to page through the whole history themselves.
+    pub fn search(&self, query: HistoryQuery) -> Result<Vec<HistoryEntry>> {
+        let history_data = self.data.read().map_err(|error| {
+            anyhow::anyhow!("Failed to acquire history read lock when searching entries: {error}")
+        })?;
+
+        let matching_entries = history_data
+            .entries
+            .iter()
+            .filter(|entry| query.matches(entry));
+
+        let history_entries = match query.limit {
+            Some(entry_limit) => matching_entries.take(entry_limit).cloned().collect(),
+            None => matching_entries.cloned().collect(),
+        };
+
+        Ok(history_entries)
+    }
+
     /// Delete an entry by ID
     pub fn delete(&self, id: &str) -> Result<bool> {
         let deleted = {
@@ -228,7 +594,7 @@ impl HistoryStorage {
         };
 
         if deleted {
-            self.save()?;
+            self.append_log_record(&HistoryLogOperation::Delete { id: id.to_string() })?;
         }
 
         Ok(deleted)
@@ -244,7 +610,20 @@ impl HistoryStorage {
             })?;
             history_data.entries.clear();
         }
-        self.save()
+        self.append_log_record(&HistoryLogOperation::Clear)
+    }
+
+    /// Stable content fingerprint used by `MergeDeduplicateByContent`: a hash
+    /// over normalized text, raw text, and the timestamp truncated to the
+    /// second, so the same dictation imported under a fresh UUID still dedupes.
+    fn content_fingerprint(entry: &HistoryEntry) -> u64 {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        entry.text.trim().hash(&mut hasher);
+        entry.raw_text.trim().hash(&mut hasher);
+        entry.timestamp.timestamp().hash(&mut hasher);
+        hasher.finish()
     }
 
     /// Import entries with the specified strategy
@@ -295,6 +674,8 @@ impl HistoryStorage {
                         .map(|entry| entry.id.clone())
                         .collect();
 
+                    let original_entry_count = entries.len();
+
                     // Filter out entries that already exist
                     let new_entries: Vec<HistoryEntry> = entries
                         .into_iter()
@@ -302,7 +683,37 @@ impl HistoryStorage {
                         .collect();
 
                     imported_count = new_entries.len();
-                    skipped_count = 0; // We'll calculate this from the original count
+                    skipped_count = original_entry_count - imported_count;
+
+                    // Prepend new entries
+                    let mut combined_entries = new_entries;
+                    combined_entries.append(&mut history_data.entries);
+
+                    // Sort by timestamp (newest first)
+                    combined_entries.sort_by(|left_entry, right_entry| {
+                        right_entry.timestamp.cmp(&left_entry.timestamp)
+                    });
+                    history_data.entries = combined_entries;
+                }
+                HistoryImportStrategy::MergeDeduplicateByContent => {
+                    // Seed the seen set with fingerprints of existing entries,
+                    // then de-dup the imported batch against both existing
+                    // entries and earlier entries in the same batch.
+                    let mut seen_fingerprints: HashSet<u64> = history_data
+                        .entries
+                        .iter()
+                        .map(Self::content_fingerprint)
+                        .collect();
+
+                    let original_entry_count = entries.len();
+
+                    let new_entries: Vec<HistoryEntry> = entries
+                        .into_iter()
+                        .filter(|entry| seen_fingerprints.insert(Self::content_fingerprint(entry)))
+                        .collect();
+
+                    imported_count = new_entries.len();
+                    skipped_count = original_entry_count - imported_count;
 
                     // Prepend new entries
                     let mut combined_entries = new_entries;
@@ -322,7 +733,9 @@ impl HistoryStorage {
             }
         }
 
-        self.save()?;
+        // Importing replaces/merges the whole entry set at once, so fold it
+        // straight into a fresh snapshot rather than logging it as a mutation.
+        self.compact()?;
 
         Ok(HistoryImportResult {
             success: true,
@@ -330,6 +743,157 @@ impl HistoryStorage {
             entries_skipped: Some(skipped_count),
         })
     }
+
+    /// Compute what `import_entries` would do for `entries`/`strategy`
+    /// without writing anything, so a caller can show a confirmation
+    /// preview before committing to the import.
+    pub fn preview_import(
+        &self,
+        entries: &[HistoryEntry],
+        strategy: HistoryImportStrategy,
+    ) -> Result<HistoryImportResult> {
+        let history_data = self.data.read().map_err(|error| {
+            anyhow::anyhow!("Failed to acquire history read lock when previewing import: {error}")
+        })?;
+
+        let (imported_count, skipped_count) = match strategy {
+            HistoryImportStrategy::Replace | HistoryImportStrategy::MergeAppend => {
+                (entries.len(), 0)
+            }
+            HistoryImportStrategy::MergeDeduplicate => {
+                let existing_entry_ids: HashSet<&str> = history_data
+                    .entries
+                    .iter()
+                    .map(|entry| entry.id.as_str())
+                    .collect();
+                let skipped_count = entries
+                    .iter()
+                    .filter(|entry| existing_entry_ids.contains(entry.id.as_str()))
+                    .count();
+                (entries.len() - skipped_count, skipped_count)
+            }
+            HistoryImportStrategy::MergeDeduplicateByContent => {
+                let mut seen_fingerprints: HashSet<u64> = history_data
+                    .entries
+                    .iter()
+                    .map(Self::content_fingerprint)
+                    .collect();
+                let skipped_count = entries
+                    .iter()
+                    .filter(|entry| !seen_fingerprints.insert(Self::content_fingerprint(entry)))
+                    .count();
+                (entries.len() - skipped_count, skipped_count)
+            }
+        };
+
+        Ok(HistoryImportResult {
+            success: true,
+            entries_imported: Some(imported_count),
+            entries_skipped: Some(skipped_count),
+        })
+    }
+
+    /// Render all history entries (newest first) in the requested format,
+    /// for backup/migration across machines. Pairs with `import_entries`.
+    pub fn export_entries(&self, format: HistoryExportFormat) -> Result<String> {
+        let entries = self.get_all(None)?;
+
+        match format {
+            HistoryExportFormat::Json => serde_json::to_string_pretty(&entries)
+                .context("Failed to serialize history entries to JSON"),
+            HistoryExportFormat::Csv => Ok(Self::entries_to_csv(&entries)),
+            HistoryExportFormat::Markdown => Ok(Self::entries_to_markdown(&entries)),
+        }
+    }
+
+    /// Render entries as CSV with columns for timestamp, text, raw_text, app
+    /// display name, window title, and browser origin
+    fn entries_to_csv(entries: &[HistoryEntry]) -> String {
+        let mut csv_content =
+            String::from("timestamp,text,raw_text,app_display_name,window_title,browser_origin\n");
+
+        for entry in entries {
+            let app_context = entry.active_app_context.as_ref();
+            let app_display_name = app_context
+                .and_then(|context| context.focused_application.as_ref())
+                .map(|application| application.display_name.as_str())
+                .unwrap_or("");
+            let window_title = app_context
+                .and_then(|context| context.focused_window.as_ref())
+                .map(|window| window.title.as_str())
+                .unwrap_or("");
+            let browser_origin = app_context
+                .and_then(|context| context.focused_browser_tab.as_ref())
+                .and_then(|tab| tab.origin.as_deref())
+                .unwrap_or("");
+
+            let row = [
+                entry.timestamp.to_rfc3339(),
+                entry.text.clone(),
+                entry.raw_text.clone(),
+                app_display_name.to_string(),
+                window_title.to_string(),
+                browser_origin.to_string(),
+            ];
+
+            csv_content.push_str(
+                &row.iter()
+                    .map(|field| Self::csv_quote_field(field))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+            csv_content.push('\n');
+        }
+
+        csv_content
+    }
+
+    /// Quote a CSV field per RFC 4180 if it contains a comma, quote, or newline
+    fn csv_quote_field(field: &str) -> String {
+        if field.contains(['"', ',', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+
+    /// Render entries as a sequence of Markdown sections, one per entry
+    fn entries_to_markdown(entries: &[HistoryEntry]) -> String {
+        let mut markdown_content = String::from("# Dictation History\n");
+
+        for entry in entries {
+            let app_context = entry.active_app_context.as_ref();
+            let app_display_name = app_context
+                .and_then(|context| context.focused_application.as_ref())
+                .map(|application| application.display_name.as_str());
+            let window_title = app_context
+                .and_then(|context| context.focused_window.as_ref())
+                .map(|window| window.title.as_str());
+            let browser_origin = app_context
+                .and_then(|context| context.focused_browser_tab.as_ref())
+                .and_then(|tab| tab.origin.as_deref());
+
+            markdown_content.push_str(&format!("\n## {}\n\n", entry.timestamp.to_rfc3339()));
+
+            let context_parts: Vec<String> = [
+                app_display_name.map(|name| format!("App: {name}")),
+                window_title.map(|title| format!("Window: {title}")),
+                browser_origin.map(|origin| format!("Origin: {origin}")),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+
+            if !context_parts.is_empty() {
+                markdown_content.push_str(&format!("*{}*\n\n", context_parts.join(" · ")));
+            }
+
+            markdown_content.push_str(&entry.text);
+            markdown_content.push('\n');
+        }
+
+        markdown_content
+    }
 }
 
 #[cfg(test)]
@@ -377,7 +941,7 @@ mod tests {
         fs::write(&history_file_path, legacy_history_content)
             .expect("failed to seed legacy history file");
 
-        let history_storage = HistoryStorage::new(temporary_history_directory.path.clone());
+        let history_storage = HistoryStorage::new(temporary_history_directory.path.clone(), None);
         let loaded_entries = history_storage
             .get_all(None)
             .expect("failed to load legacy history entries");
@@ -391,7 +955,7 @@ mod tests {
     #[test]
     fn add_entry_persists_active_app_context() {
         let temporary_history_directory = TemporaryHistoryDirectory::new();
-        let history_storage = HistoryStorage::new(temporary_history_directory.path.clone());
+        let history_storage = HistoryStorage::new(temporary_history_directory.path.clone(), None);
 
         let active_app_context_snapshot = ActiveAppContextSnapshot {
             focused_application: Some(FocusedApplication {
@@ -437,4 +1001,356 @@ mod tests {
             Some(active_app_context_snapshot)
         );
     }
+
+    #[test]
+    fn search_filters_by_term_and_app_bundle_id() {
+        let temporary_history_directory = TemporaryHistoryDirectory::new();
+        let history_storage = HistoryStorage::new(temporary_history_directory.path.clone(), None);
+
+        let vs_code_context = ActiveAppContextSnapshot {
+            focused_application: Some(FocusedApplication {
+                display_name: "Code".to_string(),
+                bundle_id: Some("com.microsoft.VSCode".to_string()),
+                process_path: None,
+            }),
+            focused_window: Some(FocusedWindow {
+                title: "notes.md".to_string(),
+            }),
+            focused_browser_tab: None,
+            event_source: FocusEventSource::Accessibility,
+            confidence_level: FocusConfidenceLevel::High,
+            captured_at: "2026-02-08T12:00:00Z".to_string(),
+        };
+
+        history_storage
+            .add_entry(
+                "Remember to deploy the service".to_string(),
+                "remember to deploy the service".to_string(),
+                Some(vs_code_context),
+            )
+            .expect("failed to add first history entry");
+
+        history_storage
+            .add_entry(
+                "Buy groceries after work".to_string(),
+                "buy groceries after work".to_string(),
+                None,
+            )
+            .expect("failed to add second history entry");
+
+        let term_matches = history_storage
+            .search(HistoryQuery {
+                term: Some("DEPLOY".to_string()),
+                ..Default::default()
+            })
+            .expect("failed to search history by term");
+        assert_eq!(term_matches.len(), 1);
+        assert_eq!(term_matches[0].text, "Remember to deploy the service");
+
+        let app_matches = history_storage
+            .search(HistoryQuery {
+                app_bundle_id: Some("com.microsoft.VSCode".to_string()),
+                ..Default::default()
+            })
+            .expect("failed to search history by app bundle id");
+        assert_eq!(app_matches.len(), 1);
+        assert_eq!(app_matches[0].text, "Remember to deploy the service");
+
+        let no_matches = history_storage
+            .search(HistoryQuery {
+                term: Some("nonexistent".to_string()),
+                ..Default::default()
+            })
+            .expect("failed to search history with no matches");
+        assert!(no_matches.is_empty());
+    }
+
+    #[test]
+    fn mutations_are_appended_to_the_log_without_rewriting_the_snapshot() {
+        let temporary_history_directory = TemporaryHistoryDirectory::new();
+        let history_storage = HistoryStorage::new(temporary_history_directory.path.clone(), None);
+
+        history_storage
+            .add_entry("First entry".to_string(), "first entry".to_string(), None)
+            .expect("failed to add first history entry");
+        history_storage
+            .add_entry("Second entry".to_string(), "second entry".to_string(), None)
+            .expect("failed to add second history entry");
+
+        let snapshot_path = temporary_history_directory.path.join("history.json");
+        let log_path = temporary_history_directory.path.join("history.log");
+
+        assert!(
+            !snapshot_path.exists(),
+            "snapshot should not be written until compaction"
+        );
+        let log_content = fs::read_to_string(&log_path).expect("failed to read history log");
+        assert_eq!(log_content.lines().count(), 2);
+
+        // Reopening storage should replay the log on top of the (empty) snapshot.
+        let reloaded_history_storage =
+            HistoryStorage::new(temporary_history_directory.path.clone(), None);
+        let reloaded_entries = reloaded_history_storage
+            .get_all(None)
+            .expect("failed to read entries reconstructed from the log");
+        assert_eq!(reloaded_entries.len(), 2);
+        assert_eq!(reloaded_entries[0].text, "Second entry");
+        assert_eq!(reloaded_entries[1].text, "First entry");
+    }
+
+    #[test]
+    fn replay_ignores_a_partial_trailing_log_line_left_by_a_crash() {
+        let temporary_history_directory = TemporaryHistoryDirectory::new();
+        let log_path = temporary_history_directory.path.join("history.log");
+
+        let complete_entry =
+            HistoryEntry::new("Saved entry".to_string(), "saved entry".to_string(), None);
+        let complete_record = serde_json::to_string(&HistoryLogOperation::Add {
+            entry: complete_entry,
+        })
+        .expect("failed to serialize complete log record");
+
+        // Simulate a crash mid-write: a full record followed by a truncated one.
+        let log_content = format!("{complete_record}\n{{\"op\":\"add\",\"entry\":{{\"id\":\"trun");
+        fs::write(&log_path, log_content).expect("failed to seed crashed history log");
+
+        let history_storage = HistoryStorage::new(temporary_history_directory.path.clone(), None);
+        let entries = history_storage
+            .get_all(None)
+            .expect("failed to read entries replayed from a crashed log");
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].text, "Saved entry");
+    }
+
+    #[test]
+    fn encrypted_snapshot_round_trips_with_the_same_key() {
+        let temporary_history_directory = TemporaryHistoryDirectory::new();
+        let encryption_key = [7u8; 32];
+        let history_storage = HistoryStorage::new(
+            temporary_history_directory.path.clone(),
+            Some(encryption_key),
+        );
+
+        history_storage
+            .import_entries(
+                vec![HistoryEntry::new(
+                    "Sensitive spoken content".to_string(),
+                    "sensitive spoken content".to_string(),
+                    None,
+                )],
+                HistoryImportStrategy::Replace,
+            )
+            .expect("failed to import entries into encrypted storage");
+
+        let snapshot_path = temporary_history_directory.path.join("history.json");
+        let snapshot_bytes = fs::read(&snapshot_path).expect("failed to read encrypted snapshot");
+        assert!(snapshot_bytes.starts_with(HISTORY_ENCRYPTION_MAGIC));
+        assert!(
+            !String::from_utf8_lossy(&snapshot_bytes).contains("Sensitive spoken content"),
+            "plaintext leaked into the encrypted snapshot"
+        );
+
+        let reloaded_history_storage = HistoryStorage::new(
+            temporary_history_directory.path.clone(),
+            Some(encryption_key),
+        );
+        let reloaded_entries = reloaded_history_storage
+            .get_all(None)
+            .expect("failed to read entries from the decrypted snapshot");
+        assert_eq!(reloaded_entries.len(), 1);
+        assert_eq!(reloaded_entries[0].text, "Sensitive spoken content");
+    }
+
+    #[test]
+    fn encrypted_snapshot_fails_to_load_with_the_wrong_key() {
+        let temporary_history_directory = TemporaryHistoryDirectory::new();
+        let encryption_key = [7u8; 32];
+        let history_storage = HistoryStorage::new(
+            temporary_history_directory.path.clone(),
+            Some(encryption_key),
+        );
+
+        history_storage
+            .import_entries(
+                vec![HistoryEntry::new(
+                    "Sensitive spoken content".to_string(),
+                    "sensitive spoken content".to_string(),
+                    None,
+                )],
+                HistoryImportStrategy::Replace,
+            )
+            .expect("failed to import entries into encrypted storage");
+
+        let snapshot_path = temporary_history_directory.path.join("history.json");
+        let wrong_key = [9u8; 32];
+        let load_result = HistoryStorage::load_from_file(&snapshot_path, Some(&wrong_key));
+
+        assert!(load_result.is_err());
+    }
+
+    #[test]
+    fn merge_deduplicate_by_content_skips_re_imports_with_fresh_uuids() {
+        let temporary_history_directory = TemporaryHistoryDirectory::new();
+        let history_storage = HistoryStorage::new(temporary_history_directory.path.clone(), None);
+
+        let timestamp = "2026-02-08T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let make_entry = |text: &str| HistoryEntry {
+            id: Uuid::new_v4().to_string(),
+            timestamp,
+            text: text.to_string(),
+            raw_text: text.to_lowercase(),
+            active_app_context: None,
+        };
+
+        let first_import_result = history_storage
+            .import_entries(
+                vec![make_entry("Buy groceries"), make_entry("Call the dentist")],
+                HistoryImportStrategy::Replace,
+            )
+            .expect("failed to seed initial entries");
+        assert_eq!(first_import_result.entries_imported, Some(2));
+        assert_eq!(first_import_result.entries_skipped, Some(0));
+
+        // Same logical entries, but with fresh UUIDs (as if re-exported from
+        // another machine), plus one internal duplicate and one genuinely new entry.
+        let second_import_result = history_storage
+            .import_entries(
+                vec![
+                    make_entry("Buy groceries"),
+                    make_entry("Call the dentist"),
+                    make_entry("Call the dentist"),
+                    make_entry("Schedule a haircut"),
+                ],
+                HistoryImportStrategy::MergeDeduplicateByContent,
+            )
+            .expect("failed to merge-deduplicate by content");
+
+        assert_eq!(second_import_result.entries_imported, Some(1));
+        assert_eq!(second_import_result.entries_skipped, Some(3));
+
+        let all_entries = history_storage
+            .get_all(None)
+            .expect("failed to read merged entries");
+        assert_eq!(all_entries.len(), 3);
+        let texts: HashSet<String> = all_entries.iter().map(|entry| entry.text.clone()).collect();
+        assert_eq!(
+            texts,
+            HashSet::from([
+                "Buy groceries".to_string(),
+                "Call the dentist".to_string(),
+                "Schedule a haircut".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn preview_import_matches_the_real_import_without_mutating_history() {
+        let temporary_history_directory = TemporaryHistoryDirectory::new();
+        let history_storage = HistoryStorage::new(temporary_history_directory.path.clone(), None);
+
+        let timestamp = "2026-02-08T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let make_entry = |text: &str| HistoryEntry {
+            id: Uuid::new_v4().to_string(),
+            timestamp,
+            text: text.to_string(),
+            raw_text: text.to_lowercase(),
+            active_app_context: None,
+        };
+
+        history_storage
+            .import_entries(
+                vec![make_entry("Buy groceries"), make_entry("Call the dentist")],
+                HistoryImportStrategy::Replace,
+            )
+            .expect("failed to seed initial entries");
+
+        let incoming = vec![
+            make_entry("Buy groceries"),
+            make_entry("Schedule a haircut"),
+        ];
+
+        let preview = history_storage
+            .preview_import(&incoming, HistoryImportStrategy::MergeDeduplicateByContent)
+            .expect("failed to preview merge-deduplicate by content");
+
+        assert_eq!(preview.entries_imported, Some(1));
+        assert_eq!(preview.entries_skipped, Some(1));
+
+        // The preview must not have changed what's actually stored.
+        let stored_entries = history_storage
+            .get_all(None)
+            .expect("failed to read stored entries");
+        assert_eq!(stored_entries.len(), 2);
+
+        let real_import = history_storage
+            .import_entries(incoming, HistoryImportStrategy::MergeDeduplicateByContent)
+            .expect("failed to merge-deduplicate by content");
+        assert_eq!(real_import.entries_imported, preview.entries_imported);
+        assert_eq!(real_import.entries_skipped, preview.entries_skipped);
+    }
+
+    #[test]
+    fn export_entries_renders_csv_with_quoted_fields_and_context_columns() {
+        let temporary_history_directory = TemporaryHistoryDirectory::new();
+        let history_storage = HistoryStorage::new(temporary_history_directory.path.clone(), None);
+
+        let active_app_context_snapshot = ActiveAppContextSnapshot {
+            focused_application: Some(FocusedApplication {
+                display_name: "Code".to_string(),
+                bundle_id: Some("com.microsoft.VSCode".to_string()),
+                process_path: None,
+            }),
+            focused_window: Some(FocusedWindow {
+                title: "notes.md".to_string(),
+            }),
+            focused_browser_tab: None,
+            event_source: FocusEventSource::Accessibility,
+            confidence_level: FocusConfidenceLevel::High,
+            captured_at: "2026-02-08T12:00:00Z".to_string(),
+        };
+
+        history_storage
+            .add_entry(
+                "Buy milk, eggs, and bread".to_string(),
+                "buy milk, eggs, and bread".to_string(),
+                Some(active_app_context_snapshot),
+            )
+            .expect("failed to add history entry");
+
+        let csv_export = history_storage
+            .export_entries(HistoryExportFormat::Csv)
+            .expect("failed to export history as CSV");
+
+        let mut csv_lines = csv_export.lines();
+        assert_eq!(
+            csv_lines.next().unwrap(),
+            "timestamp,text,raw_text,app_display_name,window_title,browser_origin"
+        );
+        let entry_row = csv_lines.next().unwrap();
+        assert!(entry_row.contains("\"Buy milk, eggs, and bread\""));
+        assert!(entry_row.contains("Code"));
+        assert!(entry_row.contains("notes.md"));
+    }
+
+    #[test]
+    fn export_entries_renders_markdown_sections() {
+        let temporary_history_directory = TemporaryHistoryDirectory::new();
+        let history_storage = HistoryStorage::new(temporary_history_directory.path.clone(), None);
+
+        history_storage
+            .add_entry(
+                "Remember the meeting".to_string(),
+                "remember the meeting".to_string(),
+                None,
+            )
+            .expect("failed to add history entry");
+
+        let markdown_export = history_storage
+            .export_entries(HistoryExportFormat::Markdown)
+            .expect("failed to export history as Markdown");
+
+        assert!(markdown_export.starts_with("# Dictation History"));
+        assert!(markdown_export.contains("Remember the meeting"));
+    }
 }
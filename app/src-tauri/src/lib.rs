@@ -2,23 +2,32 @@ use std::sync::atomic::Ordering;
 use tauri::{
     menu::{Menu, MenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Emitter, Manager,
+    AppHandle, Emitter, Listener, Manager,
 };
 
+mod active_app_context;
 mod audio;
+mod audio_capture;
+mod audio_device;
 mod audio_mute;
 mod commands;
+mod config_sync;
+mod duration;
+mod events;
 mod history;
 mod settings;
 mod state;
 
+use active_app_context::FocusWatcherHandle;
 use audio_mute::AudioMuteManager;
+use events::EventName;
 use history::HistoryStorage;
-use settings::SettingsManager;
+use settings::{HotkeyConfig, ObservableSetting, SettingsManager};
 use state::AppState;
+use std::sync::Mutex;
 
 #[cfg(desktop)]
-use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut, ShortcutState};
+use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 
 /// Check if audio mute is supported on this platform
 #[tauri::command]
@@ -43,17 +52,35 @@ pub fn run() {
             commands::text::get_server_url,
             commands::settings::get_settings,
             commands::settings::save_settings,
+            commands::settings::export_settings_toml,
             commands::settings::update_toggle_hotkey,
             commands::settings::update_hold_hotkey,
+            commands::settings::update_paste_last_hotkey,
+            commands::settings::update_app_hotkey_profile,
             commands::settings::update_selected_mic,
             commands::settings::update_sound_enabled,
+            commands::settings::update_streaming_mode,
+            commands::settings::update_sound_volume,
+            commands::settings::update_sound_output_device,
             commands::settings::update_cleanup_prompt_sections,
+            commands::settings::save_prompt_profile,
+            commands::settings::list_prompt_profiles,
+            commands::settings::delete_prompt_profile,
+            commands::settings::load_prompt_profile,
             commands::settings::update_stt_provider,
             commands::settings::update_llm_provider,
             commands::settings::update_auto_mute_audio,
+            commands::settings::update_duck_level,
+            commands::settings::update_text_injection_mode,
+            commands::settings::update_send_active_app_context_enabled,
+            commands::settings::update_telemetry_enabled,
+            commands::settings::update_focus_redaction_rules,
+            commands::settings::set_context_override,
+            commands::settings::get_effective_settings,
             is_audio_mute_supported,
             commands::history::add_history_entry,
             commands::history::get_history,
+            commands::history::search_history,
             commands::history::delete_history_entry,
             commands::history::clear_history,
             commands::overlay::resize_overlay,
@@ -68,13 +95,146 @@ pub fn run() {
             let settings_manager = SettingsManager::new(app_data_dir.clone());
             app.manage(settings_manager);
 
-            let history_storage = HistoryStorage::new(app_data_dir);
+            // TODO: derive this from the OS keychain once a key-management
+            // story lands; history is stored in plaintext until then.
+            let history_storage = HistoryStorage::new(app_data_dir, None);
             app.manage(history_storage);
 
+            let config_sync = config_sync::new_config_sync();
+            #[cfg(feature = "metrics")]
+            config_sync::spawn_metrics_flush_loop(app.handle().clone(), config_sync.clone());
+            app.manage(config_sync);
+
+            #[cfg(desktop)]
+            {
+                // Apply the user's configured hotkeys (the plugin was built
+                // with fallback defaults, before settings were loaded) and
+                // start watching the foreground app so per-app hotkey
+                // profiles can be resolved as it changes.
+                reregister_global_shortcuts(app.handle(), None);
+
+                let settings_manager = app.state::<SettingsManager>();
+                let send_active_app_context_enabled = settings_manager
+                    .get()
+                    .map(|settings| settings.send_active_app_context_enabled)
+                    .unwrap_or(false);
+
+                let focus_watcher_handle: Option<FocusWatcherHandle> =
+                    send_active_app_context_enabled
+                        .then(|| active_app_context::start_focus_watcher_in_app(app.handle()));
+                app.manage(Mutex::new(focus_watcher_handle));
+                app.manage(Mutex::<Option<audio_capture::StreamingCaptureHandle>>::new(
+                    None,
+                ));
+
+                // Keep the focus watcher and the server's copy of the
+                // cleanup prompt sections in sync with settings, no matter
+                // which write path changed them (a single `update_*`
+                // command or a full `save_settings` replace).
+                {
+                    let app_handle = app.handle().clone();
+                    settings_manager.register_observer(
+                        ObservableSetting::SendActiveAppContextEnabled,
+                        Box::new(move |settings| {
+                            let focus_watcher_state =
+                                app_handle.state::<Mutex<Option<FocusWatcherHandle>>>();
+                            let mut focus_watcher_handle = focus_watcher_state
+                                .lock()
+                                .map_err(|e| format!("Focus watcher state lock poisoned: {e}"))?;
+                            active_app_context::sync_focus_watcher_enabled(
+                                &app_handle,
+                                &mut focus_watcher_handle,
+                                settings.send_active_app_context_enabled,
+                            );
+                            Ok(())
+                        }),
+                    );
+                }
+                {
+                    let app_handle = app.handle().clone();
+                    settings_manager.register_observer(
+                        ObservableSetting::Hotkeys,
+                        Box::new(move |_settings| {
+                            let focused_app_identifier = app_handle
+                                .state::<AppState>()
+                                .focused_app_identifier
+                                .read()
+                                .map_err(|e| format!("Focused app identifier lock poisoned: {e}"))?
+                                .clone();
+                            reregister_global_shortcuts(
+                                &app_handle,
+                                focused_app_identifier.as_deref(),
+                            );
+                            Ok(())
+                        }),
+                    );
+                }
+                {
+                    let app_handle = app.handle().clone();
+                    settings_manager.register_observer(
+                        ObservableSetting::CleanupPromptSections,
+                        Box::new(move |settings| {
+                            let Some(sections) = settings.cleanup_prompt_sections.clone() else {
+                                return Ok(());
+                            };
+                            let config_sync = app_handle
+                                .state::<config_sync::ConfigSync>()
+                                .inner()
+                                .clone();
+                            tauri::async_runtime::spawn(async move {
+                                let mut sync = config_sync.write().await;
+                                if let Err(e) = sync.sync_prompt_sections(&sections).await {
+                                    log::warn!(
+                                        "Failed to sync prompt sections after settings change: {e}"
+                                    );
+                                }
+                            });
+                            Ok(())
+                        }),
+                    );
+                }
+
+                let app_handle = app.handle().clone();
+                app.listen(EventName::ActiveAppContextChanged.as_str(), move |event| {
+                    let Ok(snapshot) =
+                        serde_json::from_str::<active_app_context::ActiveAppContextSnapshot>(
+                            event.payload(),
+                        )
+                    else {
+                        return;
+                    };
+
+                    let app_identifier = snapshot
+                        .focused_application
+                        .as_ref()
+                        .map(|application| application.identifier().to_string());
+
+                    let state = app_handle.state::<AppState>();
+                    if let Ok(mut focused_app_identifier) = state.focused_app_identifier.write() {
+                        *focused_app_identifier = app_identifier.clone();
+                    }
+
+                    reregister_global_shortcuts(&app_handle, app_identifier.as_deref());
+                });
+            }
+
             // Initialize audio mute manager (may be None on unsupported platforms)
             if let Some(audio_mute_manager) = AudioMuteManager::new() {
                 app.manage(audio_mute_manager);
             }
+
+            // Initialize audio cue player (may be None if no output device is available)
+            let settings_manager = app.state::<SettingsManager>();
+            let settings = settings_manager.get().ok();
+            let sound_volume = settings.as_ref().map(|s| s.sound_volume).unwrap_or(0.3);
+            let sound_output_device_id = settings
+                .as_ref()
+                .and_then(|s| s.sound_output_device_id.clone());
+            if let Some(audio_cue_player) =
+                audio::AudioCuePlayer::new(sound_volume, sound_output_device_id.as_deref())
+            {
+                app.manage(audio_cue_player);
+            }
             // Create overlay window
             let overlay = tauri::WebviewWindowBuilder::new(
                 app,
@@ -164,14 +324,139 @@ fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Convert a setting-level modifier name (e.g. `"ctrl"`, `"Alt"`) to its
+/// `Modifiers` flag. Unrecognized names contribute no flag.
+#[cfg(desktop)]
+fn modifiers_from_config(modifier_names: &[String]) -> Modifiers {
+    modifier_names
+        .iter()
+        .fold(Modifiers::empty(), |flags, name| {
+            flags
+                | match name.to_lowercase().as_str() {
+                    "ctrl" | "control" => Modifiers::CONTROL,
+                    "alt" | "option" => Modifiers::ALT,
+                    "shift" => Modifiers::SHIFT,
+                    "cmd" | "super" | "meta" => Modifiers::SUPER,
+                    _ => Modifiers::empty(),
+                }
+        })
+}
+
+/// Convert a `HotkeyConfig` to a registerable `Shortcut`, or `None` if it's
+/// disabled or its key doesn't map to a known `Code`.
+#[cfg(desktop)]
+pub(crate) fn hotkey_config_to_shortcut(hotkey: &HotkeyConfig) -> Option<Shortcut> {
+    if !hotkey.enabled {
+        return None;
+    }
+    let code: Code = hotkey.key.parse().ok()?;
+    Some(Shortcut::new(
+        Some(modifiers_from_config(&hotkey.modifiers)),
+        code,
+    ))
+}
+
+/// Render a `HotkeyConfig` as a human-readable accelerator string (e.g.
+/// "Ctrl+Shift+D") for use in diagnostics and error messages.
+#[cfg(desktop)]
+pub(crate) fn format_hotkey_for_display(hotkey: &HotkeyConfig) -> String {
+    hotkey
+        .modifiers
+        .iter()
+        .map(|m| {
+            let mut chars = m.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .chain(std::iter::once(hotkey.key.clone()))
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// Resolve the `EffectiveHotkeys` currently in effect for `app_identifier`
+/// (or the default profile's, if `None`), or `None` if the settings manager
+/// can't be read.
+#[cfg(desktop)]
+pub(crate) fn current_effective_hotkeys(
+    app: &AppHandle,
+    app_identifier: Option<&str>,
+) -> Option<settings::EffectiveHotkeys> {
+    let settings_manager = app.state::<SettingsManager>();
+    let settings = settings_manager.get().ok()?;
+    Some(settings.effective_hotkeys(app_identifier))
+}
+
+/// Resolve the toggle/hold shortcuts currently in effect for `app_identifier`
+/// (or the default profile's, if `None`), for both registering them and
+/// recognizing them in the shortcut handler.
+#[cfg(desktop)]
+fn current_effective_shortcuts(
+    app: &AppHandle,
+    app_identifier: Option<&str>,
+) -> (Option<Shortcut>, Option<Shortcut>) {
+    let Some(effective_hotkeys) = current_effective_hotkeys(app, app_identifier) else {
+        return (None, None);
+    };
+
+    (
+        hotkey_config_to_shortcut(&effective_hotkeys.toggle),
+        hotkey_config_to_shortcut(&effective_hotkeys.hold),
+    )
+}
+
+/// Re-register the global toggle/hold shortcuts for the hotkeys in effect for
+/// `app_identifier`, recording any registration failures on `AppState` so the
+/// frontend can surface them.
+///
+/// Note: `paste_last` is never registered as a global shortcut (only its
+/// settings-update command is wired up), so `paste_last_error` is left
+/// untouched here; it's a pre-existing gap outside the scope of this pass.
+#[cfg(desktop)]
+fn reregister_global_shortcuts(app: &AppHandle, app_identifier: Option<&str>) {
+    let global_shortcut = app.global_shortcut();
+    let _ = global_shortcut.unregister_all();
+
+    let Some(effective_hotkeys) = current_effective_hotkeys(app, app_identifier) else {
+        return;
+    };
+    let toggle_shortcut = hotkey_config_to_shortcut(&effective_hotkeys.toggle);
+    let hold_shortcut = hotkey_config_to_shortcut(&effective_hotkeys.hold);
+    let mut errors = state::ShortcutErrors::default();
+
+    if let Some(toggle_shortcut) = toggle_shortcut {
+        if let Err(e) = global_shortcut.register(toggle_shortcut) {
+            errors.toggle_error = Some(state::ShortcutError::classify(
+                e.to_string(),
+                Some(format_hotkey_for_display(&effective_hotkeys.toggle)),
+            ));
+        }
+    }
+    if let Some(hold_shortcut) = hold_shortcut {
+        if let Err(e) = global_shortcut.register(hold_shortcut) {
+            errors.hold_error = Some(state::ShortcutError::classify(
+                e.to_string(),
+                Some(format_hotkey_for_display(&effective_hotkeys.hold)),
+            ));
+        }
+    }
+
+    if let Ok(mut shortcut_errors) = app.state::<AppState>().shortcut_errors.write() {
+        *shortcut_errors = errors;
+    }
+}
+
 #[cfg(desktop)]
 fn build_global_shortcut_plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
-    // Define shortcuts
-    let toggle_shortcut = Shortcut::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::Space);
-    let hold_shortcut = Shortcut::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::Period);
+    // Fall back to the defaults at plugin-build time: settings aren't loaded
+    // until `setup()`, which calls `reregister_global_shortcuts` to apply
+    // the user's actual configuration (and any per-app profile) over these.
+    let toggle_shortcut = hotkey_config_to_shortcut(&HotkeyConfig::default_toggle());
+    let hold_shortcut = hotkey_config_to_shortcut(&HotkeyConfig::default_hold());
 
     tauri_plugin_global_shortcut::Builder::new()
-        .with_shortcuts([toggle_shortcut, hold_shortcut])
+        .with_shortcuts(toggle_shortcut.into_iter().chain(hold_shortcut))
         .expect("Failed to register global shortcuts - check if another instance is running")
         .with_handler(move |app, shortcut, event| {
             let state = app.state::<AppState>();
@@ -184,22 +469,103 @@ fn build_global_shortcut_plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
                 .as_ref()
                 .map(|s| s.auto_mute_audio)
                 .unwrap_or(false);
+            let duck_level = settings.as_ref().and_then(|s| s.duck_level);
+            let streaming_mode = settings.as_ref().map(|s| s.streaming_mode).unwrap_or(false);
 
-            // Get audio mute manager if available
+            // Get audio mute manager and cue player if available
             let audio_mute_manager = app.try_state::<AudioMuteManager>();
+            let audio_cue_player = app.try_state::<audio::AudioCuePlayer>();
+
+            // Helper to start the low-latency streaming-capture path
+            // (see `AppSettings::streaming_mode`); falls back to no-op (the
+            // existing batch record-then-upload flow handles things from the
+            // frontend) if we're not paired with a server or capture fails
+            // to start.
+            let begin_streaming_capture = || {
+                let Some(config_sync) = app.try_state::<config_sync::ConfigSync>() else {
+                    return;
+                };
+                let Ok(sync_state) = config_sync.try_read() else {
+                    return;
+                };
+                let (Some(server_url), Some(client_uuid)) =
+                    (sync_state.server_url(), sync_state.client_uuid())
+                else {
+                    return;
+                };
+                let upload_target = audio_capture::StreamingUploadTarget {
+                    client: sync_state.client(),
+                    server_url: server_url.to_string(),
+                    client_uuid: client_uuid.to_string(),
+                };
+                drop(sync_state);
+
+                let Some(device) = settings_manager
+                    .get()
+                    .ok()
+                    .and_then(|s| audio_device::resolve_input_device(&s))
+                else {
+                    log::warn!("Streaming capture: no input device available");
+                    return;
+                };
+
+                match audio_capture::start_streaming_capture(device, upload_target) {
+                    Ok(handle) => {
+                        if let Some(slot) = app
+                            .try_state::<Mutex<Option<audio_capture::StreamingCaptureHandle>>>()
+                        {
+                            if let Ok(mut slot) = slot.lock() {
+                                *slot = Some(handle);
+                            }
+                        }
+                    }
+                    Err(e) => log::warn!("Failed to start streaming capture: {e}"),
+                }
+            };
+
+            // Helper to stop the streaming-capture path; dropping the handle
+            // flushes the final frame and sends the end-of-stream marker.
+            let end_streaming_capture = || {
+                if let Some(slot) =
+                    app.try_state::<Mutex<Option<audio_capture::StreamingCaptureHandle>>>()
+                {
+                    if let Ok(mut slot) = slot.lock() {
+                        slot.take();
+                    }
+                }
+            };
 
-            // Helper to mute audio
+            // Helper to start recording: play the start cue (so it's audible
+            // before audio gets muted/ducked), then mute if enabled.
             let mute_audio = || {
+                if sound_enabled {
+                    if let Some(player) = &audio_cue_player {
+                        player.play(audio::SoundType::RecordingStart);
+                    }
+                }
                 if auto_mute_audio {
                     if let Some(manager) = &audio_mute_manager {
-                        if let Err(e) = manager.mute() {
-                            log::warn!("Failed to mute audio: {}", e);
+                        // Skip muting/ducking entirely when nothing is
+                        // playing - there's nothing to attenuate, and it
+                        // avoids a pointless mute/unmute pair on every
+                        // recording. Backends that can't tell default to
+                        // reporting the device active, so this is a no-op
+                        // there.
+                        if manager.is_device_active().unwrap_or(true) {
+                            let mute_result = match duck_level {
+                                Some(duck_level) => manager.duck(duck_level),
+                                None => manager.mute(),
+                            };
+                            if let Err(e) = mute_result {
+                                log::warn!("Failed to mute audio: {}", e);
+                            }
                         }
                     }
                 }
             };
 
-            // Helper to unmute audio
+            // Helper to stop recording: unmute if enabled, then play the
+            // stop cue so it isn't swallowed by our own mute.
             let unmute_audio = || {
                 if auto_mute_audio {
                     if let Some(manager) = &audio_mute_manager {
@@ -208,14 +574,22 @@ fn build_global_shortcut_plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
                         }
                     }
                 }
+                if sound_enabled {
+                    if let Some(player) = &audio_cue_player {
+                        player.play(audio::SoundType::RecordingStop);
+                    }
+                }
             };
 
-            let toggle_shortcut =
-                Shortcut::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::Space);
-            let hold_shortcut =
-                Shortcut::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::Period);
+            let focused_app_identifier = state
+                .focused_app_identifier
+                .read()
+                .ok()
+                .and_then(|guard| guard.clone());
+            let (toggle_shortcut, hold_shortcut) =
+                current_effective_shortcuts(app, focused_app_identifier.as_deref());
 
-            if shortcut == &toggle_shortcut {
+            if Some(shortcut) == toggle_shortcut.as_ref() {
                 // Toggle mode: only respond to Pressed, ignore Released
                 if matches!(event.state, ShortcutState::Pressed) {
                     let is_recording = state.is_recording.load(Ordering::SeqCst);
@@ -225,25 +599,22 @@ fn build_global_shortcut_plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
                         state.is_recording.store(false, Ordering::SeqCst);
                         log::info!("Toggle: stopping recording");
                         unmute_audio();
-                        if sound_enabled {
-                            audio::play_sound(audio::SoundType::RecordingStop);
+                        if streaming_mode {
+                            end_streaming_capture();
                         }
                         let _ = app.emit("recording-stop", ());
                     } else {
                         // Start recording
                         state.is_recording.store(true, Ordering::SeqCst);
                         log::info!("Toggle: starting recording");
-                        // Play sound BEFORE muting so it's audible
-                        if sound_enabled {
-                            audio::play_sound(audio::SoundType::RecordingStart);
-                            // Brief delay to let sound play before muting
-                            std::thread::sleep(std::time::Duration::from_millis(150));
-                        }
                         mute_audio();
+                        if streaming_mode {
+                            begin_streaming_capture();
+                        }
                         let _ = app.emit("recording-start", ());
                     }
                 }
-            } else if shortcut == &hold_shortcut {
+            } else if Some(shortcut) == hold_shortcut.as_ref() {
                 // Hold-to-Record: respond to both Pressed and Released
                 match event.state {
                     ShortcutState::Pressed => {
@@ -252,13 +623,10 @@ fn build_global_shortcut_plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
                             // First press - start recording
                             state.is_recording.store(true, Ordering::SeqCst);
                             log::info!("Hold: starting recording");
-                            // Play sound BEFORE muting so it's audible
-                            if sound_enabled {
-                                audio::play_sound(audio::SoundType::RecordingStart);
-                                // Brief delay to let sound play before muting
-                                std::thread::sleep(std::time::Duration::from_millis(150));
-                            }
                             mute_audio();
+                            if streaming_mode {
+                                begin_streaming_capture();
+                            }
                             let _ = app.emit("recording-start", ());
                         }
                     }
@@ -268,8 +636,8 @@ fn build_global_shortcut_plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
                             state.is_recording.store(false, Ordering::SeqCst);
                             log::info!("Hold: stopping recording");
                             unmute_audio();
-                            if sound_enabled {
-                                audio::play_sound(audio::SoundType::RecordingStop);
+                            if streaming_mode {
+                                end_streaming_capture();
                             }
                             let _ = app.emit("recording-stop", ());
                         }
@@ -1,8 +1,25 @@
+use crate::active_app_context::FocusRedactionRule;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::RwLock;
 
+/// How transcribed text is inserted into the focused field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextInjectionMode {
+    /// Paste via the clipboard (saving and restoring its prior contents).
+    /// Fast for long text, but can be mangled by apps that intercept paste.
+    #[default]
+    Clipboard,
+    /// Type each character directly through synthetic keystrokes, leaving
+    /// the clipboard untouched. Slower for long text, but works in apps
+    /// that reject pasted input.
+    Keystroke,
+}
+
 /// Configuration for a hotkey combination
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct HotkeyConfig {
@@ -10,15 +27,299 @@ pub struct HotkeyConfig {
     pub modifiers: Vec<String>,
     /// The main key (e.g., "Space")
     pub key: String,
+    /// Whether this hotkey is active
+    #[serde(default = "default_hotkey_enabled")]
+    pub enabled: bool,
+}
+
+fn default_hotkey_enabled() -> bool {
+    true
 }
 
 impl Default for HotkeyConfig {
     fn default() -> Self {
+        Self::default_toggle()
+    }
+}
+
+impl HotkeyConfig {
+    /// The default toggle-recording hotkey.
+    pub fn default_toggle() -> Self {
         Self {
             modifiers: vec!["ctrl".to_string(), "alt".to_string()],
             key: "Space".to_string(),
+            enabled: true,
+        }
+    }
+
+    /// The default hold-to-record hotkey.
+    pub fn default_hold() -> Self {
+        Self {
+            modifiers: vec!["ctrl".to_string(), "alt".to_string()],
+            key: "Backquote".to_string(),
+            enabled: true,
+        }
+    }
+
+    /// The default paste-last-transcript hotkey.
+    pub fn default_paste_last() -> Self {
+        Self {
+            modifiers: vec!["ctrl".to_string(), "alt".to_string()],
+            key: "Period".to_string(),
+            enabled: true,
+        }
+    }
+
+    /// A `+`-joined representation of this hotkey, e.g. `"ctrl+alt+Space"`.
+    /// Modifiers are lowercased; the key's case is preserved.
+    pub fn to_shortcut_string(&self) -> String {
+        let mut parts: Vec<String> = self.modifiers.iter().map(|m| m.to_lowercase()).collect();
+        parts.push(self.key.clone());
+        parts.join("+")
+    }
+
+    /// Whether `self` and `other` describe the same key combination,
+    /// ignoring case and modifier order.
+    pub fn is_same_as(&self, other: &Self) -> bool {
+        if !self.key.eq_ignore_ascii_case(&other.key) {
+            return false;
+        }
+
+        if self.modifiers.len() != other.modifiers.len() {
+            return false;
+        }
+
+        let mut self_modifiers: Vec<String> =
+            self.modifiers.iter().map(|m| m.to_lowercase()).collect();
+        let mut other_modifiers: Vec<String> =
+            other.modifiers.iter().map(|m| m.to_lowercase()).collect();
+        self_modifiers.sort();
+        other_modifiers.sort();
+
+        self_modifiers == other_modifiers
+    }
+}
+
+/// Which of the three configurable hotkeys is being referred to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyType {
+    Toggle,
+    Hold,
+    PasteLast,
+}
+
+impl HotkeyType {
+    pub const ALL: [HotkeyType; 3] = [HotkeyType::Toggle, HotkeyType::Hold, HotkeyType::PasteLast];
+
+    /// The `LocalOnlySetting` this hotkey type is persisted under.
+    pub fn local_only_setting(self) -> LocalOnlySetting {
+        match self {
+            HotkeyType::Toggle => LocalOnlySetting::ToggleHotkey,
+            HotkeyType::Hold => LocalOnlySetting::HoldHotkey,
+            HotkeyType::PasteLast => LocalOnlySetting::PasteLastHotkey,
+        }
+    }
+
+    /// A short human-readable name for use in conflict messages.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            HotkeyType::Toggle => "toggle",
+            HotkeyType::Hold => "hold",
+            HotkeyType::PasteLast => "paste last",
+        }
+    }
+}
+
+/// Settings that are local to this device and never synced to the server.
+///
+/// Only the hotkey settings are modeled here so far; this will grow as more
+/// settings move onto the local/server-synced settings split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalOnlySetting {
+    ToggleHotkey,
+    HoldHotkey,
+    PasteLastHotkey,
+}
+
+/// Errors surfaced when validating a hotkey change.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingsError {
+    /// The new hotkey collides with another hotkey in the same profile.
+    HotkeyConflict {
+        conflicting_type: HotkeyType,
+        hotkey: HotkeyConfig,
+    },
+    /// The new hotkey collides with a hotkey the app profile would otherwise
+    /// inherit from the default profile. Resolving it may mean adding an
+    /// explicit override for the other hotkey type in this app's profile,
+    /// rather than changing the default.
+    CrossProfileHotkeyConflict {
+        conflicting_type: HotkeyType,
+        app_identifier: String,
+    },
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HotkeyConflict {
+                conflicting_type,
+                hotkey,
+            } => write!(
+                f,
+                "Conflicts with the {} hotkey ({})",
+                conflicting_type.display_name(),
+                hotkey.to_shortcut_string()
+            ),
+            Self::CrossProfileHotkeyConflict {
+                conflicting_type,
+                app_identifier,
+            } => write!(
+                f,
+                "Conflicts with the {} hotkey inherited from the default profile for {}",
+                conflicting_type.display_name(),
+                app_identifier
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+/// Per-app hotkey overrides, keyed by app identifier in
+/// `AppSettings::app_hotkey_profiles`. Any hotkey left `None` falls back to
+/// the default profile's hotkey of that type.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AppHotkeyProfile {
+    #[serde(default)]
+    pub toggle_hotkey: Option<HotkeyConfig>,
+    #[serde(default)]
+    pub hold_hotkey: Option<HotkeyConfig>,
+    #[serde(default)]
+    pub paste_last_hotkey: Option<HotkeyConfig>,
+}
+
+impl AppHotkeyProfile {
+    fn hotkey_for(&self, hotkey_type: HotkeyType) -> Option<&HotkeyConfig> {
+        match hotkey_type {
+            HotkeyType::Toggle => self.toggle_hotkey.as_ref(),
+            HotkeyType::Hold => self.hold_hotkey.as_ref(),
+            HotkeyType::PasteLast => self.paste_last_hotkey.as_ref(),
+        }
+    }
+}
+
+/// Scopes a hotkey-conflict check to either the default (global) profile or
+/// a specific app's override profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyProfileScope<'a> {
+    Default,
+    App(&'a str),
+}
+
+impl<'a> HotkeyProfileScope<'a> {
+    fn app_identifier(self) -> Option<&'a str> {
+        match self {
+            HotkeyProfileScope::Default => None,
+            HotkeyProfileScope::App(app_identifier) => Some(app_identifier),
+        }
+    }
+}
+
+/// The toggle/hold/paste-last hotkeys actually in effect for some app, after
+/// applying any per-app profile overrides over the defaults.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectiveHotkeys {
+    pub toggle: HotkeyConfig,
+    pub hold: HotkeyConfig,
+    pub paste_last: HotkeyConfig,
+}
+
+/// A partial override of dictation-relevant settings, applied when the
+/// current context (a browser origin from `normalize_browser_document_origin`,
+/// or an app identifier from `FocusedApplication::identifier`) matches this
+/// override's key in `AppSettings::context_overrides`. Any field left `None`
+/// falls back to the global default, the same inheritance model as
+/// `AppHotkeyProfile`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ContextSettingsOverride {
+    #[serde(default)]
+    pub cleanup_prompt_sections: Option<CleanupPromptSections>,
+    #[serde(default)]
+    pub stt_provider: Option<String>,
+    #[serde(default)]
+    pub llm_provider: Option<String>,
+    #[serde(default)]
+    pub auto_paste_enabled: Option<bool>,
+    #[serde(default)]
+    pub auto_mute_audio: Option<bool>,
+}
+
+/// The settings actually in effect for some context, after applying a
+/// matching `ContextSettingsOverride` over the global defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EffectiveSettings {
+    pub cleanup_prompt_sections: Option<CleanupPromptSections>,
+    pub stt_provider: Option<String>,
+    pub llm_provider: Option<String>,
+    pub auto_paste_enabled: bool,
+    pub auto_mute_audio: bool,
+}
+
+/// Check whether `new_hotkey` would conflict with another hotkey of a
+/// different type in the default profile.
+pub fn check_hotkey_conflict(
+    new_hotkey: &HotkeyConfig,
+    settings: &AppSettings,
+    hotkey_type: HotkeyType,
+) -> Option<SettingsError> {
+    check_hotkey_conflict_in_profile(
+        new_hotkey,
+        settings,
+        hotkey_type,
+        HotkeyProfileScope::Default,
+    )
+}
+
+/// Check whether `new_hotkey` would conflict with another hotkey of a
+/// different type within `profile_scope`. An app-scoped check compares
+/// against the hotkeys actually in effect for that app (its own overrides,
+/// falling back to the default profile), so conflicts are only ever raised
+/// within the same profile, and conflicts with an inherited default hotkey
+/// are reported as `CrossProfileHotkeyConflict` rather than a plain one.
+pub fn check_hotkey_conflict_in_profile(
+    new_hotkey: &HotkeyConfig,
+    settings: &AppSettings,
+    hotkey_type: HotkeyType,
+    profile_scope: HotkeyProfileScope,
+) -> Option<SettingsError> {
+    for other_type in HotkeyType::ALL {
+        if other_type == hotkey_type {
+            continue;
         }
+
+        let (other_hotkey, inherited_from_default) =
+            settings.effective_hotkey_with_origin(other_type, profile_scope.app_identifier());
+
+        if !new_hotkey.is_same_as(other_hotkey) {
+            continue;
+        }
+
+        return Some(
+            match (inherited_from_default, profile_scope.app_identifier()) {
+                (true, Some(app_identifier)) => SettingsError::CrossProfileHotkeyConflict {
+                    conflicting_type: other_type,
+                    app_identifier: app_identifier.to_string(),
+                },
+                _ => SettingsError::HotkeyConflict {
+                    conflicting_type: other_type,
+                    hotkey: other_hotkey.clone(),
+                },
+            },
+        );
     }
+
+    None
 }
 
 /// Configuration for a single prompt section
@@ -80,6 +381,16 @@ pub struct AppSettings {
     #[serde(default = "default_hold_hotkey")]
     pub hold_hotkey: HotkeyConfig,
 
+    /// Hotkey for pasting the last transcript
+    #[serde(default = "default_paste_last_hotkey")]
+    pub paste_last_hotkey: HotkeyConfig,
+
+    /// Per-app hotkey overrides, keyed by app identifier (see
+    /// `FocusedApplication::identifier`). Any hotkey type a profile doesn't
+    /// override falls back to the default profile's hotkey of that type.
+    #[serde(default)]
+    pub app_hotkey_profiles: HashMap<String, AppHotkeyProfile>,
+
     /// Selected microphone device ID (None = system default)
     #[serde(default)]
     pub selected_mic_id: Option<String>,
@@ -88,10 +399,32 @@ pub struct AppSettings {
     #[serde(default = "default_sound_enabled")]
     pub sound_enabled: bool,
 
+    /// Stream microphone audio to the server as Opus frames while recording,
+    /// instead of buffering the whole clip and uploading it once recording
+    /// stops. Lower latency on fast links; falls back to the batch path if
+    /// the streaming capture fails to start.
+    #[serde(default)]
+    pub streaming_mode: bool,
+
+    /// Volume (0.0-1.0) for the recording-start/stop/error cues.
+    #[serde(default = "default_sound_volume")]
+    pub sound_volume: f32,
+
+    /// Output device to play sound cues through (the `cpal` device name), or
+    /// `None` to use the system default output device.
+    #[serde(default)]
+    pub sound_output_device_id: Option<String>,
+
     /// Cleanup prompt sections configuration
     #[serde(default)]
     pub cleanup_prompt_sections: Option<CleanupPromptSections>,
 
+    /// Named, saved snapshots of `cleanup_prompt_sections`, keyed by profile
+    /// name (e.g. "email", "code", "dictation"), so a user can switch their
+    /// whole prompt setup instantly instead of re-editing each section.
+    #[serde(default)]
+    pub prompt_profiles: HashMap<String, CleanupPromptSections>,
+
     /// Selected STT provider (None = use server default)
     #[serde(default)]
     pub stt_provider: Option<String>,
@@ -103,45 +436,455 @@ pub struct AppSettings {
     /// Whether to automatically mute system audio during recording
     #[serde(default)]
     pub auto_mute_audio: bool,
+
+    /// When set, recording sets the system output volume to this level
+    /// (0.0-1.0) instead of fully muting it, restoring the exact
+    /// prior volume when recording stops. Only takes effect when
+    /// `auto_mute_audio` is also enabled. `None` keeps the existing hard-mute
+    /// behavior. This is the same `auto_mute_audio` + `duck_level` pair that
+    /// `build_global_shortcut_plugin`'s `mute_audio`/`unmute_audio` closures
+    /// branch on, so a UI only needs these two fields to offer an
+    /// off/mute/duck three-way choice - no separate mode enum required.
+    #[serde(default)]
+    pub duck_level: Option<f32>,
+
+    /// How transcribed text is inserted into the focused field.
+    #[serde(default)]
+    pub text_injection_mode: TextInjectionMode,
+
+    /// Whether to watch the foreground app so per-app hotkey profiles can be
+    /// resolved. Off by default since it involves reading the title/app
+    /// currently focused on the user's desktop.
+    #[serde(default)]
+    pub send_active_app_context_enabled: bool,
+
+    /// Whether anonymous usage metrics (recording counts, provider choice,
+    /// error counts) are periodically pushed to the server. Off by default;
+    /// has no effect unless the app was built with the `metrics` feature.
+    #[serde(default)]
+    pub telemetry_enabled: bool,
+
+    /// Rules for redacting or suppressing active-app-context snapshots
+    /// before they're emitted, e.g. to keep banking sites or a password
+    /// manager's window titles from ever reaching history or the LLM
+    /// cleanup step. Evaluated in order, first match wins.
+    #[serde(default)]
+    pub focus_redaction_rules: Vec<FocusRedactionRule>,
+
+    /// Whether transcribed text is auto-pasted into the focused field.
+    #[serde(default = "default_auto_paste_enabled")]
+    pub auto_paste_enabled: bool,
+
+    /// Per-origin/per-app overrides of dictation settings, keyed by a
+    /// browser origin (see `normalize_browser_document_origin`) or app
+    /// identifier (see `FocusedApplication::identifier`).
+    #[serde(default)]
+    pub context_overrides: HashMap<String, ContextSettingsOverride>,
+
+    /// On-disk schema version, used to migrate older settings files forward
+    /// instead of discarding them on an unrecognized shape. See
+    /// `CURRENT_SETTINGS_SCHEMA_VERSION`.
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+}
+
+fn default_auto_paste_enabled() -> bool {
+    true
 }
 
 fn default_toggle_hotkey() -> HotkeyConfig {
-    HotkeyConfig {
-        modifiers: vec!["ctrl".to_string(), "alt".to_string()],
-        key: "Space".to_string(),
-    }
+    HotkeyConfig::default_toggle()
 }
 
 fn default_hold_hotkey() -> HotkeyConfig {
-    HotkeyConfig {
-        modifiers: vec!["ctrl".to_string(), "alt".to_string()],
-        key: "Period".to_string(),
-    }
+    HotkeyConfig::default_hold()
+}
+
+fn default_paste_last_hotkey() -> HotkeyConfig {
+    HotkeyConfig::default_paste_last()
 }
 
 fn default_sound_enabled() -> bool {
     true
 }
 
+fn default_sound_volume() -> f32 {
+    0.3
+}
+
+fn default_schema_version() -> u32 {
+    CURRENT_SETTINGS_SCHEMA_VERSION
+}
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             toggle_hotkey: default_toggle_hotkey(),
             hold_hotkey: default_hold_hotkey(),
+            paste_last_hotkey: default_paste_last_hotkey(),
+            app_hotkey_profiles: HashMap::new(),
             selected_mic_id: None,
             sound_enabled: true,
+            streaming_mode: false,
+            sound_volume: default_sound_volume(),
+            sound_output_device_id: None,
             cleanup_prompt_sections: None,
+            prompt_profiles: HashMap::new(),
             stt_provider: None,
             llm_provider: None,
             auto_mute_audio: false,
+            duck_level: None,
+            text_injection_mode: TextInjectionMode::default(),
+            send_active_app_context_enabled: false,
+            telemetry_enabled: false,
+            focus_redaction_rules: Vec::new(),
+            auto_paste_enabled: default_auto_paste_enabled(),
+            context_overrides: HashMap::new(),
+            schema_version: default_schema_version(),
+        }
+    }
+}
+
+impl AppSettings {
+    fn default_hotkey_for(&self, hotkey_type: HotkeyType) -> &HotkeyConfig {
+        match hotkey_type {
+            HotkeyType::Toggle => &self.toggle_hotkey,
+            HotkeyType::Hold => &self.hold_hotkey,
+            HotkeyType::PasteLast => &self.paste_last_hotkey,
+        }
+    }
+
+    /// Resolve the hotkey in effect for `hotkey_type` when `app_identifier`
+    /// is focused (or the default profile's hotkey if `app_identifier` is
+    /// `None` or has no override for this type). Also reports whether the
+    /// hotkey was inherited from the default profile, which
+    /// `check_hotkey_conflict_in_profile` uses to distinguish same-profile
+    /// conflicts from cross-profile ones.
+    fn effective_hotkey_with_origin(
+        &self,
+        hotkey_type: HotkeyType,
+        app_identifier: Option<&str>,
+    ) -> (&HotkeyConfig, bool) {
+        let profile_override = app_identifier
+            .and_then(|app_identifier| self.app_hotkey_profiles.get(app_identifier))
+            .and_then(|profile| profile.hotkey_for(hotkey_type));
+
+        match profile_override {
+            Some(hotkey) => (hotkey, false),
+            None => (self.default_hotkey_for(hotkey_type), true),
+        }
+    }
+
+    /// Resolve the toggle/hold/paste-last hotkeys actually in effect for the
+    /// given foreground app (or the defaults if `app_identifier` is `None`).
+    pub fn effective_hotkeys(&self, app_identifier: Option<&str>) -> EffectiveHotkeys {
+        EffectiveHotkeys {
+            toggle: self
+                .effective_hotkey_with_origin(HotkeyType::Toggle, app_identifier)
+                .0
+                .clone(),
+            hold: self
+                .effective_hotkey_with_origin(HotkeyType::Hold, app_identifier)
+                .0
+                .clone(),
+            paste_last: self
+                .effective_hotkey_with_origin(HotkeyType::PasteLast, app_identifier)
+                .0
+                .clone(),
+        }
+    }
+
+    /// Resolve the dictation settings actually in effect for `context_key` (a
+    /// browser origin or app identifier, see `ContextSettingsOverride`),
+    /// falling back to the global defaults for any field the matching
+    /// override leaves unset. Falls back to the global defaults entirely if
+    /// `context_key` is `None` or has no override.
+    pub fn effective_settings(&self, context_key: Option<&str>) -> EffectiveSettings {
+        let context_override =
+            context_key.and_then(|context_key| self.context_overrides.get(context_key));
+
+        EffectiveSettings {
+            cleanup_prompt_sections: context_override
+                .and_then(|context_override| context_override.cleanup_prompt_sections.clone())
+                .or_else(|| self.cleanup_prompt_sections.clone()),
+            stt_provider: context_override
+                .and_then(|context_override| context_override.stt_provider.clone())
+                .or_else(|| self.stt_provider.clone()),
+            llm_provider: context_override
+                .and_then(|context_override| context_override.llm_provider.clone())
+                .or_else(|| self.llm_provider.clone()),
+            auto_paste_enabled: context_override
+                .and_then(|context_override| context_override.auto_paste_enabled)
+                .unwrap_or(self.auto_paste_enabled),
+            auto_mute_audio: context_override
+                .and_then(|context_override| context_override.auto_mute_audio)
+                .unwrap_or(self.auto_mute_audio),
+        }
+    }
+}
+
+/// Current on-disk settings schema version. Bump this and append a
+/// corresponding entry to `SETTINGS_MIGRATIONS` whenever a field is
+/// renamed, relocated, or otherwise needs a shape change older files won't
+/// already satisfy via `#[serde(default)]`.
+const CURRENT_SETTINGS_SCHEMA_VERSION: u32 = 1;
+
+/// One migration step, upgrading the raw JSON value from the version at
+/// its index in `SETTINGS_MIGRATIONS` to the next. Kept as plain value
+/// transforms (rather than deserializing into versioned structs) so a
+/// migration can survive fields it doesn't know about yet.
+type SettingsMigration = fn(serde_json::Value) -> serde_json::Value;
+
+/// `SETTINGS_MIGRATIONS[v]` upgrades a settings file from schema version
+/// `v` to `v + 1`. Unversioned files (no `schema_version` field) are
+/// treated as version 0. There is no v0 -> v1 migration to make yet since
+/// `schema_version` is the first schema change and every field introduced
+/// before it already defaults via `#[serde(default)]`.
+const SETTINGS_MIGRATIONS: &[SettingsMigration] = &[];
+
+/// Name of the optional layered-config file consulted by `resolve_settings`,
+/// read from the same app data directory as `settings.json`.
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Prefix shared by every environment variable `resolve_settings` consults.
+const ENV_VAR_PREFIX: &str = "TAMBOURINE_";
+
+/// A partial set of settings overrides loaded from `config.toml`. Every
+/// field is optional: a field left out of the file leaves the layer below
+/// (the built-in defaults) untouched. Mirrors the override/inheritance model
+/// already used by `AppHotkeyProfile`/`ContextSettingsOverride`, just for the
+/// whole settings file instead of a single app or context.
+///
+/// Only the scalar, non-relational settings are exposed here - hotkeys,
+/// per-app profiles, context overrides, and redaction rules are rich enough
+/// to warrant the existing JSON-based settings UI rather than a flat TOML
+/// file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+struct ConfigFileOverrides {
+    sound_enabled: Option<bool>,
+    streaming_mode: Option<bool>,
+    sound_volume: Option<f32>,
+    stt_provider: Option<String>,
+    llm_provider: Option<String>,
+    auto_mute_audio: Option<bool>,
+    duck_level: Option<f32>,
+    text_injection_mode: Option<TextInjectionMode>,
+    send_active_app_context_enabled: Option<bool>,
+    telemetry_enabled: Option<bool>,
+    auto_paste_enabled: Option<bool>,
+}
+
+impl ConfigFileOverrides {
+    /// Overwrite every field in `settings` that this layer has an opinion
+    /// on, leaving the rest of `settings` exactly as it was.
+    fn apply_to(self, settings: &mut AppSettings) {
+        if let Some(sound_enabled) = self.sound_enabled {
+            settings.sound_enabled = sound_enabled;
+        }
+        if let Some(streaming_mode) = self.streaming_mode {
+            settings.streaming_mode = streaming_mode;
+        }
+        if let Some(sound_volume) = self.sound_volume {
+            settings.sound_volume = sound_volume;
+        }
+        if let Some(stt_provider) = self.stt_provider {
+            settings.stt_provider = Some(stt_provider);
+        }
+        if let Some(llm_provider) = self.llm_provider {
+            settings.llm_provider = Some(llm_provider);
+        }
+        if let Some(auto_mute_audio) = self.auto_mute_audio {
+            settings.auto_mute_audio = auto_mute_audio;
+        }
+        if let Some(duck_level) = self.duck_level {
+            settings.duck_level = Some(duck_level);
+        }
+        if let Some(text_injection_mode) = self.text_injection_mode {
+            settings.text_injection_mode = text_injection_mode;
+        }
+        if let Some(send_active_app_context_enabled) = self.send_active_app_context_enabled {
+            settings.send_active_app_context_enabled = send_active_app_context_enabled;
+        }
+        if let Some(telemetry_enabled) = self.telemetry_enabled {
+            settings.telemetry_enabled = telemetry_enabled;
+        }
+        if let Some(auto_paste_enabled) = self.auto_paste_enabled {
+            settings.auto_paste_enabled = auto_paste_enabled;
         }
     }
 }
 
+/// Apply `TAMBOURINE_*` environment variable overrides on top of `settings`,
+/// the final (highest-priority) layer in `resolve_settings`. Only variables
+/// that are actually set are applied - unset or unparseable ones leave the
+/// file/default value alone, the latter logging a warning since it usually
+/// indicates a typo in the deployment's environment.
+fn apply_env_overrides(settings: &mut AppSettings) {
+    fn env_var(name: &str) -> Option<String> {
+        std::env::var(format!("{ENV_VAR_PREFIX}{name}")).ok()
+    }
+
+    fn parsed_env_var<T: std::str::FromStr>(name: &str) -> Option<T> {
+        let raw_value = env_var(name)?;
+        match raw_value.parse() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                log::warn!("Ignoring {ENV_VAR_PREFIX}{name}: couldn't parse '{raw_value}'");
+                None
+            }
+        }
+    }
+
+    if let Some(sound_enabled) = parsed_env_var("SOUND_ENABLED") {
+        settings.sound_enabled = sound_enabled;
+    }
+    if let Some(streaming_mode) = parsed_env_var("STREAMING_MODE") {
+        settings.streaming_mode = streaming_mode;
+    }
+    if let Some(sound_volume) = parsed_env_var("SOUND_VOLUME") {
+        settings.sound_volume = sound_volume;
+    }
+    if let Some(stt_provider) = env_var("STT_PROVIDER") {
+        settings.stt_provider = Some(stt_provider);
+    }
+    if let Some(llm_provider) = env_var("LLM_PROVIDER") {
+        settings.llm_provider = Some(llm_provider);
+    }
+    if let Some(auto_mute_audio) = parsed_env_var("AUTO_MUTE_AUDIO") {
+        settings.auto_mute_audio = auto_mute_audio;
+    }
+    if let Some(duck_level) = parsed_env_var("DUCK_LEVEL") {
+        settings.duck_level = Some(duck_level);
+    }
+    if let Some(send_active_app_context_enabled) = parsed_env_var("SEND_ACTIVE_APP_CONTEXT_ENABLED")
+    {
+        settings.send_active_app_context_enabled = send_active_app_context_enabled;
+    }
+    if let Some(telemetry_enabled) = parsed_env_var("TELEMETRY_ENABLED") {
+        settings.telemetry_enabled = telemetry_enabled;
+    }
+    if let Some(auto_paste_enabled) = parsed_env_var("AUTO_PASTE_ENABLED") {
+        settings.auto_paste_enabled = auto_paste_enabled;
+    }
+}
+
+/// Resolve `AppSettings` by layering, in increasing priority:
+/// 1. `AppSettings::default()`
+/// 2. An optional `config.toml` next to `settings.json` in `app_data_dir`
+/// 3. `TAMBOURINE_*` environment variables
+///
+/// Each layer only overrides the fields it has an opinion on; anything it
+/// leaves unset falls through to the layer below. Used by `SettingsManager`
+/// to seed settings on first launch (before a `settings.json` exists), so
+/// headless/enterprise/CI deployments can pin settings without ever going
+/// through the UI; once `settings.json` exists, it takes over as the
+/// source of truth and this pipeline isn't consulted again.
+pub fn resolve_settings(app_data_dir: &std::path::Path) -> AppSettings {
+    let mut settings = AppSettings::default();
+
+    let config_file_path = app_data_dir.join(CONFIG_FILE_NAME);
+    if let Ok(content) = fs::read_to_string(&config_file_path) {
+        match toml::from_str::<ConfigFileOverrides>(&content) {
+            Ok(overrides) => overrides.apply_to(&mut settings),
+            Err(e) => log::warn!(
+                "Failed to parse {}: {e}, ignoring it",
+                config_file_path.display()
+            ),
+        }
+    }
+
+    apply_env_overrides(&mut settings);
+
+    settings
+}
+
+/// Serialize `settings` as a pretty-printed TOML document, for headless/CI
+/// deployments that want to capture the settings currently in effect as a
+/// `config.toml` they can check in and re-apply via `resolve_settings`.
+pub fn to_toml_string(settings: &AppSettings) -> Result<String, String> {
+    toml::to_string_pretty(settings).map_err(|e| format!("Failed to serialize settings: {e}"))
+}
+
+// ============================================================================
+// RUNTIME SETTINGS OBSERVERS
+// ============================================================================
+
+/// A setting some runtime subsystem reacts to when it changes, no matter
+/// which write path changed it (a single `update_*` call, a full
+/// `save_settings` replace, or - eventually - import/factory-reset). Add a
+/// variant here plus one `SettingsManager::register_observer` call where
+/// that subsystem starts up, instead of hand-wiring reconciliation into
+/// every place that can touch the underlying field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObservableSetting {
+    SendActiveAppContextEnabled,
+    CleanupPromptSections,
+    /// `toggle_hotkey`, `hold_hotkey`, or `app_hotkey_profiles` changed, so
+    /// the registered global shortcuts need to be rebuilt from the settings
+    /// now in effect.
+    Hotkeys,
+}
+
+/// A subsystem's reaction to an `ObservableSetting` changing value. Receives
+/// the settings after the write. A returned `Err` is logged as a warning,
+/// not treated as a failure of the settings write that triggered it.
+pub type SettingsObserver = Box<dyn Fn(&AppSettings) -> Result<(), String> + Send + Sync>;
+
+/// Registry of `SettingsObserver`s keyed by the `ObservableSetting` they
+/// react to, dispatched by `SettingsManager` whenever a write actually
+/// changes that setting's value.
+#[derive(Default)]
+struct SettingsObservers {
+    observers: RwLock<HashMap<ObservableSetting, Vec<SettingsObserver>>>,
+}
+
+impl SettingsObservers {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `observer` to run whenever `setting` changes value.
+    fn register(&self, setting: ObservableSetting, observer: SettingsObserver) {
+        if let Ok(mut observers) = self.observers.write() {
+            observers.entry(setting).or_default().push(observer);
+        }
+    }
+
+    /// Diff `old` vs `new` and run every observer registered for each
+    /// `ObservableSetting` whose value actually changed, collecting errors
+    /// instead of stopping at the first one.
+    fn notify_changes(&self, old: &AppSettings, new: &AppSettings) -> Vec<String> {
+        let mut changed = Vec::new();
+        if old.send_active_app_context_enabled != new.send_active_app_context_enabled {
+            changed.push(ObservableSetting::SendActiveAppContextEnabled);
+        }
+        if old.cleanup_prompt_sections != new.cleanup_prompt_sections {
+            changed.push(ObservableSetting::CleanupPromptSections);
+        }
+        if old.toggle_hotkey != new.toggle_hotkey
+            || old.hold_hotkey != new.hold_hotkey
+            || old.app_hotkey_profiles != new.app_hotkey_profiles
+        {
+            changed.push(ObservableSetting::Hotkeys);
+        }
+
+        let Ok(observers) = self.observers.read() else {
+            return Vec::new();
+        };
+        changed
+            .into_iter()
+            .flat_map(|setting| observers.get(&setting).into_iter().flatten())
+            .filter_map(|observer| observer(new).err())
+            .collect()
+    }
+}
+
 /// Manages loading and saving of application settings
 pub struct SettingsManager {
     settings: RwLock<AppSettings>,
     file_path: PathBuf,
+    observers: SettingsObservers,
 }
 
 impl SettingsManager {
@@ -154,22 +897,110 @@ impl SettingsManager {
             let _ = fs::create_dir_all(parent);
         }
 
-        // Load existing settings or use defaults
-        let settings = Self::load_from_file(&file_path).unwrap_or_default();
+        // Load existing settings, or seed them via the default/config.toml/env
+        // layered resolution pipeline if `settings.json` doesn't exist yet.
+        let mut settings =
+            Self::load_from_file(&file_path).unwrap_or_else(|| resolve_settings(&app_data_dir));
+
+        // A previously-selected mic that's no longer connected (e.g.
+        // unplugged) shouldn't silently break recording - fall back to the
+        // system default instead.
+        if let Some(selected_mic_id) = settings.selected_mic_id.as_deref() {
+            if !crate::audio_device::input_device_exists(selected_mic_id) {
+                log::warn!(
+                    "Selected microphone '{selected_mic_id}' is no longer connected, \
+                     reverting to the system default"
+                );
+                settings.selected_mic_id = None;
+            }
+        }
 
         Self {
             settings: RwLock::new(settings),
             file_path,
+            observers: SettingsObservers::new(),
+        }
+    }
+
+    /// Register `observer` to run whenever `setting` changes value, from any
+    /// write path (a single `update_*` call or a full `update` replace).
+    pub fn register_observer(&self, setting: ObservableSetting, observer: SettingsObserver) {
+        self.observers.register(setting, observer);
+    }
+
+    /// Run every observer whose `ObservableSetting` changed between `old`
+    /// and the settings currently in effect, logging (but not propagating)
+    /// any observer errors - a failed runtime reconciliation shouldn't make
+    /// the settings write itself look like it failed.
+    fn notify_observers(&self, old: &AppSettings, new: &AppSettings) {
+        for error in self.observers.notify_changes(old, new) {
+            log::warn!("Settings observer failed: {error}");
         }
     }
 
-    /// Load settings from the JSON file
+    /// Load settings from the JSON file, migrating an older
+    /// `schema_version` forward. Returns `None` if the file doesn't exist,
+    /// isn't valid JSON at all, or still doesn't match `AppSettings` after
+    /// migration - in which case the unreadable file is first backed up to
+    /// `settings.json.bak-<unix timestamp>` so the caller's fall back to
+    /// defaults doesn't destroy it.
     fn load_from_file(file_path: &PathBuf) -> Option<AppSettings> {
         let content = fs::read_to_string(file_path).ok()?;
-        serde_json::from_str(&content).ok()
+
+        let mut value: serde_json::Value = match serde_json::from_str(&content) {
+            Ok(value) => value,
+            Err(e) => {
+                log::warn!("Settings file is not valid JSON ({e}), backing it up");
+                Self::backup_unreadable_settings_file(file_path, &content);
+                return None;
+            }
+        };
+
+        let mut version = value
+            .get("schema_version")
+            .and_then(|version| version.as_u64())
+            .unwrap_or(0) as usize;
+        while let Some(migration) = SETTINGS_MIGRATIONS.get(version) {
+            value = migration(value);
+            version += 1;
+        }
+
+        match serde_json::from_value(value) {
+            Ok(settings) => Some(settings),
+            Err(e) => {
+                log::warn!(
+                    "Settings file didn't match the expected shape after migration ({e}), \
+                     backing it up"
+                );
+                Self::backup_unreadable_settings_file(file_path, &content);
+                None
+            }
+        }
+    }
+
+    /// Copy an unreadable settings file's original content aside instead of
+    /// letting it get silently overwritten once we fall back to defaults.
+    fn backup_unreadable_settings_file(file_path: &PathBuf, original_content: &str) {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        let backup_file_name = format!(
+            "{}.bak-{timestamp}",
+            file_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("settings.json")
+        );
+        let backup_path = file_path.with_file_name(backup_file_name);
+        if let Err(e) = fs::write(&backup_path, original_content) {
+            log::warn!("Failed to back up unreadable settings file: {e}");
+        }
     }
 
-    /// Save current settings to disk
+    /// Save current settings to disk, writing to a temp file and
+    /// `fs::rename`-ing it over `settings.json` so a crash mid-write can't
+    /// leave behind a truncated/corrupt file.
     pub fn save(&self) -> Result<(), String> {
         let settings = self
             .settings
@@ -179,8 +1010,11 @@ impl SettingsManager {
         let content = serde_json::to_string_pretty(&*settings)
             .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
-        fs::write(&self.file_path, content)
-            .map_err(|e| format!("Failed to write settings file: {}", e))?;
+        let temp_path = self.file_path.with_extension("json.tmp");
+        fs::write(&temp_path, content)
+            .map_err(|e| format!("Failed to write settings temp file: {}", e))?;
+        fs::rename(&temp_path, &self.file_path)
+            .map_err(|e| format!("Failed to replace settings file: {}", e))?;
 
         Ok(())
     }
@@ -195,14 +1029,16 @@ impl SettingsManager {
 
     /// Update settings and save to disk
     pub fn update(&self, new_settings: AppSettings) -> Result<(), String> {
-        {
+        let old_settings = {
             let mut settings = self
                 .settings
                 .write()
                 .map_err(|e| format!("Failed to write settings: {}", e))?;
-            *settings = new_settings;
-        }
-        self.save()
+            std::mem::replace(&mut *settings, new_settings.clone())
+        };
+        self.save()?;
+        self.notify_observers(&old_settings, &new_settings);
+        Ok(())
     }
 
     /// Update the toggle hotkey
@@ -229,6 +1065,47 @@ impl SettingsManager {
         self.save()
     }
 
+    /// Update the paste-last-transcript hotkey
+    pub fn update_paste_last_hotkey(&self, hotkey: HotkeyConfig) -> Result<(), String> {
+        {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| format!("Failed to write settings: {}", e))?;
+            settings.paste_last_hotkey = hotkey;
+        }
+        self.save()
+    }
+
+    /// Set or clear the hotkey profile override for a specific app. Passing
+    /// `None` removes the app's profile entirely, reverting it to the
+    /// default hotkeys.
+    pub fn update_app_hotkey_profile(
+        &self,
+        app_identifier: String,
+        profile: Option<AppHotkeyProfile>,
+    ) -> Result<(), String> {
+        let (old_settings, new_settings) = {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| format!("Failed to write settings: {}", e))?;
+            let old_settings = settings.clone();
+            match profile {
+                Some(profile) => {
+                    settings.app_hotkey_profiles.insert(app_identifier, profile);
+                }
+                None => {
+                    settings.app_hotkey_profiles.remove(&app_identifier);
+                }
+            }
+            (old_settings, settings.clone())
+        };
+        self.save()?;
+        self.notify_observers(&old_settings, &new_settings);
+        Ok(())
+    }
+
     /// Update the selected microphone
     pub fn update_selected_mic(&self, mic_id: Option<String>) -> Result<(), String> {
         {
@@ -253,17 +1130,137 @@ impl SettingsManager {
         self.save()
     }
 
+    /// Update streaming-mode setting
+    pub fn update_streaming_mode(&self, enabled: bool) -> Result<(), String> {
+        {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| format!("Failed to write settings: {}", e))?;
+            settings.streaming_mode = enabled;
+        }
+        self.save()
+    }
+
+    /// Update the volume of sound cues
+    pub fn update_sound_volume(&self, volume: f32) -> Result<(), String> {
+        {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| format!("Failed to write settings: {}", e))?;
+            settings.sound_volume = volume.clamp(0.0, 1.0);
+        }
+        self.save()
+    }
+
+    /// Set or clear the output device sound cues are played through
+    pub fn update_sound_output_device(&self, device_id: Option<String>) -> Result<(), String> {
+        {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| format!("Failed to write settings: {}", e))?;
+            settings.sound_output_device_id = device_id;
+        }
+        self.save()
+    }
+
     /// Update the cleanup prompt sections setting
     pub fn update_cleanup_prompt_sections(
         &self,
         sections: Option<CleanupPromptSections>,
     ) -> Result<(), String> {
-        {
+        let (old_settings, new_settings) = {
             let mut settings = self
                 .settings
                 .write()
                 .map_err(|e| format!("Failed to write settings: {}", e))?;
+            let old_settings = settings.clone();
             settings.cleanup_prompt_sections = sections;
+            (old_settings, settings.clone())
+        };
+        self.save()?;
+        self.notify_observers(&old_settings, &new_settings);
+        Ok(())
+    }
+
+    /// Save the currently active cleanup prompt sections as a named,
+    /// reusable profile, overwriting any existing profile of that name.
+    pub fn save_prompt_profile(&self, name: String) -> Result<(), String> {
+        let sections = {
+            let settings = self
+                .settings
+                .read()
+                .map_err(|e| format!("Failed to read settings: {}", e))?;
+            settings.cleanup_prompt_sections.clone().unwrap_or_default()
+        };
+        self.set_prompt_profile(name, sections)
+    }
+
+    /// Store `sections` under `name` in `prompt_profiles`, overwriting any
+    /// existing profile of that name. Unlike `save_prompt_profile`, this
+    /// doesn't read the currently active sections - used directly when
+    /// restoring profiles from an imported bundle.
+    pub fn set_prompt_profile(
+        &self,
+        name: String,
+        sections: CleanupPromptSections,
+    ) -> Result<(), String> {
+        {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| format!("Failed to write settings: {}", e))?;
+            settings.prompt_profiles.insert(name, sections);
+        }
+        self.save()
+    }
+
+    /// Look up a saved prompt profile by name, without changing the
+    /// currently active cleanup prompt sections.
+    pub fn get_prompt_profile(&self, name: &str) -> Result<CleanupPromptSections, String> {
+        let settings = self
+            .settings
+            .read()
+            .map_err(|e| format!("Failed to read settings: {}", e))?;
+        settings
+            .prompt_profiles
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("No prompt profile named '{name}'"))
+    }
+
+    /// All saved prompt profiles, keyed by name.
+    pub fn get_all_prompt_profiles(
+        &self,
+    ) -> Result<HashMap<String, CleanupPromptSections>, String> {
+        let settings = self
+            .settings
+            .read()
+            .map_err(|e| format!("Failed to read settings: {}", e))?;
+        Ok(settings.prompt_profiles.clone())
+    }
+
+    /// The names of all saved prompt profiles, sorted alphabetically.
+    pub fn list_prompt_profiles(&self) -> Result<Vec<String>, String> {
+        let settings = self
+            .settings
+            .read()
+            .map_err(|e| format!("Failed to read settings: {}", e))?;
+        let mut names: Vec<String> = settings.prompt_profiles.keys().cloned().collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// Delete a saved prompt profile by name. A no-op if it doesn't exist.
+    pub fn delete_prompt_profile(&self, name: &str) -> Result<(), String> {
+        {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| format!("Failed to write settings: {}", e))?;
+            settings.prompt_profiles.remove(name);
         }
         self.save()
     }
@@ -303,4 +1300,120 @@ impl SettingsManager {
         }
         self.save()
     }
+
+    /// Set or clear the duck level. Passing `None` reverts to hard-muting
+    /// system audio while `auto_mute_audio` is enabled.
+    pub fn update_duck_level(&self, duck_level: Option<f32>) -> Result<(), String> {
+        {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| format!("Failed to write settings: {}", e))?;
+            settings.duck_level = duck_level;
+        }
+        self.save()
+    }
+
+    /// Update how transcribed text is inserted into the focused field
+    pub fn update_text_injection_mode(&self, mode: TextInjectionMode) -> Result<(), String> {
+        {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| format!("Failed to write settings: {}", e))?;
+            settings.text_injection_mode = mode;
+        }
+        self.save()
+    }
+
+    /// Update whether the foreground app is watched for per-app hotkey
+    /// profiles
+    pub fn update_send_active_app_context_enabled(&self, enabled: bool) -> Result<(), String> {
+        let (old_settings, new_settings) = {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| format!("Failed to write settings: {}", e))?;
+            let old_settings = settings.clone();
+            settings.send_active_app_context_enabled = enabled;
+            (old_settings, settings.clone())
+        };
+        self.save()?;
+        self.notify_observers(&old_settings, &new_settings);
+        Ok(())
+    }
+
+    /// Update whether anonymous usage metrics are pushed to the server
+    pub fn update_telemetry_enabled(&self, enabled: bool) -> Result<(), String> {
+        {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| format!("Failed to write settings: {}", e))?;
+            settings.telemetry_enabled = enabled;
+        }
+        self.save()
+    }
+
+    /// Replace the full list of focus-redaction rules
+    pub fn update_focus_redaction_rules(
+        &self,
+        rules: Vec<FocusRedactionRule>,
+    ) -> Result<(), String> {
+        {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| format!("Failed to write settings: {}", e))?;
+            settings.focus_redaction_rules = rules;
+        }
+        self.save()
+    }
+
+    /// Set or clear the per-origin/per-app settings override for
+    /// `context_key`. Passing `None` removes the override entirely,
+    /// reverting that context to the global defaults.
+    pub fn set_context_override(
+        &self,
+        context_key: String,
+        context_override: Option<ContextSettingsOverride>,
+    ) -> Result<(), String> {
+        {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| format!("Failed to write settings: {}", e))?;
+            match context_override {
+                Some(context_override) => {
+                    settings
+                        .context_overrides
+                        .insert(context_key, context_override);
+                }
+                None => {
+                    settings.context_overrides.remove(&context_key);
+                }
+            }
+        }
+        self.save()
+    }
+
+    /// Resolve the dictation settings in effect for `context_key`, see
+    /// `AppSettings::effective_settings`.
+    pub fn get_effective_settings(
+        &self,
+        context_key: Option<&str>,
+    ) -> Result<EffectiveSettings, String> {
+        self.settings
+            .read()
+            .map(|settings| settings.effective_settings(context_key))
+            .map_err(|e| format!("Failed to read settings: {}", e))
+    }
 }
+
+#[cfg(test)]
+#[path = "tests/hotkey_config_tests.rs"]
+mod hotkey_config_tests;
+
+#[cfg(test)]
+#[path = "tests/settings_commands_tests.rs"]
+mod settings_commands_tests;
@@ -2,15 +2,82 @@ use serde::{Deserialize, Serialize};
 use std::sync::atomic::AtomicBool;
 use std::sync::RwLock;
 
+#[cfg(desktop)]
+use tauri::AppHandle;
+#[cfg(desktop)]
+use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+/// Classification of why a global shortcut failed to register, so the
+/// frontend can react appropriately instead of just showing a raw message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutErrorKind {
+    /// Another application already holds this binding.
+    AlreadyBoundByAnotherApp,
+    /// The key combination doesn't map to a valid accelerator.
+    InvalidAccelerator,
+    /// The OS denied the registration (e.g. missing accessibility permission).
+    PermissionDenied,
+    /// Doesn't match any of the above known cases.
+    Unknown,
+}
+
+/// A structured shortcut registration failure, replacing the previous
+/// free-form error strings so the UI can show actionable messages (e.g.
+/// "Ctrl+Shift+D is already used by another app - pick another") and offer a
+/// retry once the user has freed the conflicting binding.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShortcutError {
+    /// The raw error message returned by the shortcut plugin.
+    pub raw_message: String,
+    /// Best-effort classification of `raw_message`.
+    pub kind: ShortcutErrorKind,
+    /// Human-readable accelerator (e.g. "Ctrl+Shift+D") that failed to
+    /// register, if known.
+    pub conflicting_binding: Option<String>,
+}
+
+impl ShortcutError {
+    /// Classify a raw registration error message from the shortcut plugin.
+    ///
+    /// The plugin's error type isn't pattern-matchable from here, so this
+    /// works off substrings of `to_string()` output, which is the only
+    /// stable surface available.
+    pub fn classify(raw_message: String, conflicting_binding: Option<String>) -> Self {
+        let lower = raw_message.to_lowercase();
+        let kind = if lower.contains("already registered")
+            || lower.contains("already in use")
+            || lower.contains("hotkey is already")
+        {
+            ShortcutErrorKind::AlreadyBoundByAnotherApp
+        } else if lower.contains("invalid") || lower.contains("parse") {
+            ShortcutErrorKind::InvalidAccelerator
+        } else if lower.contains("permission")
+            || lower.contains("denied")
+            || lower.contains("accessibility")
+        {
+            ShortcutErrorKind::PermissionDenied
+        } else {
+            ShortcutErrorKind::Unknown
+        };
+
+        Self {
+            raw_message,
+            kind,
+            conflicting_binding,
+        }
+    }
+}
+
 /// Tracks errors from shortcut registration attempts
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ShortcutErrors {
-    /// Error message if toggle shortcut failed to register
-    pub toggle_error: Option<String>,
-    /// Error message if hold shortcut failed to register
-    pub hold_error: Option<String>,
-    /// Error message if paste_last shortcut failed to register
-    pub paste_last_error: Option<String>,
+    /// Error if toggle shortcut failed to register
+    pub toggle_error: Option<ShortcutError>,
+    /// Error if hold shortcut failed to register
+    pub hold_error: Option<ShortcutError>,
+    /// Error if paste_last shortcut failed to register
+    pub paste_last_error: Option<ShortcutError>,
 }
 
 impl ShortcutErrors {
@@ -41,4 +108,72 @@ pub struct AppState {
     pub toggle_key_held: AtomicBool,
     /// Tracks errors from shortcut registration attempts
     pub shortcut_errors: RwLock<ShortcutErrors>,
+    /// Identifier of the currently-focused app (see
+    /// `FocusedApplication::identifier`), used to resolve per-app hotkey
+    /// profile overrides. `None` until the focus watcher reports a snapshot.
+    pub focused_app_identifier: RwLock<Option<String>>,
+}
+
+#[cfg(desktop)]
+impl AppState {
+    /// Re-attempt registration of only the shortcuts that previously failed
+    /// (i.e. whose `*_error` is currently `Some`), updating `shortcut_errors`
+    /// in place and returning a fresh `ShortcutRegistrationResult`.
+    ///
+    /// `paste_last` is never registered as a global shortcut at all (a
+    /// pre-existing gap outside this method's scope), so
+    /// `paste_last_registered`/`paste_last_error` are left as-is.
+    pub fn retry_failed_shortcuts(&self, app: &AppHandle) -> ShortcutRegistrationResult {
+        let app_identifier = self
+            .focused_app_identifier
+            .read()
+            .ok()
+            .and_then(|guard| guard.clone());
+
+        let mut errors = self
+            .shortcut_errors
+            .read()
+            .map(|g| g.clone())
+            .unwrap_or_default();
+
+        if let Some(effective_hotkeys) =
+            crate::current_effective_hotkeys(app, app_identifier.as_deref())
+        {
+            let global_shortcut = app.global_shortcut();
+
+            if errors.toggle_error.is_some() {
+                errors.toggle_error = crate::hotkey_config_to_shortcut(&effective_hotkeys.toggle)
+                    .and_then(|shortcut| global_shortcut.register(shortcut).err())
+                    .map(|e| {
+                        ShortcutError::classify(
+                            e.to_string(),
+                            Some(crate::format_hotkey_for_display(&effective_hotkeys.toggle)),
+                        )
+                    });
+            }
+            if errors.hold_error.is_some() {
+                errors.hold_error = crate::hotkey_config_to_shortcut(&effective_hotkeys.hold)
+                    .and_then(|shortcut| global_shortcut.register(shortcut).err())
+                    .map(|e| {
+                        ShortcutError::classify(
+                            e.to_string(),
+                            Some(crate::format_hotkey_for_display(&effective_hotkeys.hold)),
+                        )
+                    });
+            }
+        }
+
+        let result = ShortcutRegistrationResult {
+            toggle_registered: errors.toggle_error.is_none(),
+            hold_registered: errors.hold_error.is_none(),
+            paste_last_registered: errors.paste_last_error.is_none(),
+            errors: errors.clone(),
+        };
+
+        if let Ok(mut shortcut_errors) = self.shortcut_errors.write() {
+            *shortcut_errors = errors;
+        }
+
+        result
+    }
 }
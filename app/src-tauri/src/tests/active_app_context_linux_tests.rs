@@ -0,0 +1,33 @@
+use super::{
+    accessible_role_is_editable_text, accessible_role_is_secure_text,
+    supported_browser_from_application_name,
+};
+use atspi::Role;
+
+#[test]
+fn supported_browser_from_application_name_supports_common_linux_browsers() {
+    assert_eq!(
+        supported_browser_from_application_name("firefox").map(|browser| browser.display_name()),
+        Some("Firefox")
+    );
+    assert_eq!(
+        supported_browser_from_application_name("google-chrome")
+            .map(|browser| browser.display_name()),
+        Some("Google Chrome")
+    );
+    assert!(supported_browser_from_application_name("gedit").is_none());
+}
+
+#[test]
+fn accessible_role_is_editable_text_matches_entry_and_terminal_roles() {
+    assert!(accessible_role_is_editable_text(Role::Entry));
+    assert!(accessible_role_is_editable_text(Role::Terminal));
+    assert!(accessible_role_is_editable_text(Role::PasswordText));
+    assert!(!accessible_role_is_editable_text(Role::PushButton));
+}
+
+#[test]
+fn accessible_role_is_secure_text_matches_only_password_fields() {
+    assert!(accessible_role_is_secure_text(Role::PasswordText));
+    assert!(!accessible_role_is_secure_text(Role::Entry));
+}
@@ -0,0 +1,106 @@
+use super::{apply_focus_redaction_rules, FocusRedactionAction, FocusRedactionRule};
+use crate::active_app_context::{
+    ActiveAppContextSnapshot, FocusConfidenceLevel, FocusEventSource, FocusedApplication,
+    FocusedBrowserTab, FocusedWindow,
+};
+
+fn snapshot_with_bank_tab() -> ActiveAppContextSnapshot {
+    ActiveAppContextSnapshot {
+        focused_application: Some(FocusedApplication {
+            display_name: "Google Chrome".to_string(),
+            bundle_id: Some("com.google.Chrome".to_string()),
+            process_path: None,
+        }),
+        focused_window: Some(FocusedWindow {
+            title: "Account Overview - Google Chrome".to_string(),
+        }),
+        focused_browser_tab: Some(FocusedBrowserTab {
+            title: Some("Account Overview".to_string()),
+            origin: Some("https://online.chase.com".to_string()),
+            browser: Some("Google Chrome".to_string()),
+        }),
+        focused_text_element: None,
+        event_source: FocusEventSource::Accessibility,
+        confidence_level: FocusConfidenceLevel::High,
+        captured_at: "2026-01-01T00:00:00+00:00".to_string(),
+    }
+}
+
+#[test]
+fn no_rules_match_returns_snapshot_unchanged() {
+    let rules = [FocusRedactionRule {
+        pattern: "*.unrelated.example".to_string(),
+        action: FocusRedactionAction::Suppress,
+    }];
+    let snapshot = apply_focus_redaction_rules(&rules, snapshot_with_bank_tab()).unwrap();
+    assert_eq!(
+        snapshot.focused_browser_tab.unwrap().origin.as_deref(),
+        Some("https://online.chase.com")
+    );
+}
+
+#[test]
+fn suppress_action_drops_the_snapshot() {
+    let rules = [FocusRedactionRule {
+        pattern: "*.chase.com".to_string(),
+        action: FocusRedactionAction::Suppress,
+    }];
+    assert!(apply_focus_redaction_rules(&rules, snapshot_with_bank_tab()).is_none());
+}
+
+#[test]
+fn redact_action_replaces_titles_and_origin() {
+    let rules = [FocusRedactionRule {
+        pattern: "*.chase.com".to_string(),
+        action: FocusRedactionAction::Redact,
+    }];
+    let snapshot = apply_focus_redaction_rules(&rules, snapshot_with_bank_tab()).unwrap();
+    assert_eq!(snapshot.focused_window.unwrap().title, "[redacted]");
+    let browser_tab = snapshot.focused_browser_tab.unwrap();
+    assert_eq!(browser_tab.title.as_deref(), Some("[redacted]"));
+    assert_eq!(browser_tab.origin.as_deref(), Some("[redacted]"));
+}
+
+#[test]
+fn origin_only_action_keeps_origin_but_strips_titles() {
+    let rules = [FocusRedactionRule {
+        pattern: "*.chase.com".to_string(),
+        action: FocusRedactionAction::OriginOnly,
+    }];
+    let snapshot = apply_focus_redaction_rules(&rules, snapshot_with_bank_tab()).unwrap();
+    assert_eq!(snapshot.focused_window.unwrap().title, "[redacted]");
+    let browser_tab = snapshot.focused_browser_tab.unwrap();
+    assert_eq!(browser_tab.title, None);
+    assert_eq!(
+        browser_tab.origin.as_deref(),
+        Some("https://online.chase.com")
+    );
+}
+
+#[test]
+fn first_match_wins_when_multiple_rules_could_apply() {
+    let rules = [
+        FocusRedactionRule {
+            pattern: "*.chase.com".to_string(),
+            action: FocusRedactionAction::OriginOnly,
+        },
+        FocusRedactionRule {
+            pattern: "*".to_string(),
+            action: FocusRedactionAction::Suppress,
+        },
+    ];
+    let snapshot = apply_focus_redaction_rules(&rules, snapshot_with_bank_tab()).unwrap();
+    assert_eq!(
+        snapshot.focused_browser_tab.unwrap().origin.as_deref(),
+        Some("https://online.chase.com")
+    );
+}
+
+#[test]
+fn pattern_matches_are_case_insensitive_over_app_display_name() {
+    let rules = [FocusRedactionRule {
+        pattern: "google chr?me".to_string(),
+        action: FocusRedactionAction::Suppress,
+    }];
+    assert!(apply_focus_redaction_rules(&rules, snapshot_with_bank_tab()).is_none());
+}
@@ -60,15 +60,23 @@ fn infer_browser_tab_title_from_window_title_strips_browser_suffix() {
 #[test]
 fn determine_focus_confidence_level_prioritizes_origin_signal() {
     assert_eq!(
-        determine_focus_confidence_level(true, true, true),
+        determine_focus_confidence_level(true, true, true, false),
         FocusConfidenceLevel::High
     );
     assert_eq!(
-        determine_focus_confidence_level(true, false, false),
+        determine_focus_confidence_level(true, false, false, false),
         FocusConfidenceLevel::Medium
     );
     assert_eq!(
-        determine_focus_confidence_level(false, false, false),
+        determine_focus_confidence_level(false, false, false, false),
+        FocusConfidenceLevel::Low
+    );
+}
+
+#[test]
+fn determine_focus_confidence_level_downgrades_for_secure_text_fields() {
+    assert_eq!(
+        determine_focus_confidence_level(true, true, true, true),
         FocusConfidenceLevel::Low
     );
 }
@@ -1,26 +1,80 @@
 use std::sync::{Arc, Mutex};
 
 use super::shared::{
-    decide_mute_transition, decide_unmute_transition, MuteTransitionAction, MuteTransitionDecision,
+    decide_duck_transition, decide_mute_all_except_process_transition,
+    decide_mute_process_transition, decide_mute_scope_transition, decide_mute_transition,
+    decide_unmute_transition, MuteTransitionAction, MuteTransitionDecision,
+};
+use super::{
+    AudioControlError, AudioMuteManager, AudioSession, MuteScope, MuteState,
+    SessionReconciliationEvent, SystemAudioControl,
 };
-use super::{AudioControlError, AudioMuteManager, MuteState, SystemAudioControl};
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 struct FakeAudioControllerState {
     is_muted: bool,
     is_muted_error: Option<String>,
     set_muted_error: Option<String>,
     set_muted_calls: Vec<bool>,
+    volume: f32,
+    set_volume_calls: Vec<f32>,
+    is_muted_for_process_calls: Vec<u32>,
+    set_muted_for_process_calls: Vec<(u32, bool)>,
+    set_muted_for_scope_calls: Vec<(MuteScope, bool)>,
+    sessions: Vec<AudioSession>,
+}
+
+impl Default for FakeAudioControllerState {
+    fn default() -> Self {
+        Self {
+            is_muted: false,
+            is_muted_error: None,
+            set_muted_error: None,
+            set_muted_calls: Vec::new(),
+            volume: 1.0,
+            set_volume_calls: Vec::new(),
+            is_muted_for_process_calls: Vec::new(),
+            set_muted_for_process_calls: Vec::new(),
+            set_muted_for_scope_calls: Vec::new(),
+            sessions: Vec::new(),
+        }
+    }
 }
 
 #[derive(Clone)]
 struct FakeAudioController {
     state: Arc<Mutex<FakeAudioControllerState>>,
+    external_change_listener: Arc<Mutex<Option<Box<dyn Fn() + Send + Sync>>>>,
+    session_event_listeners:
+        Arc<Mutex<Vec<(u32, Box<dyn Fn(SessionReconciliationEvent) + Send + Sync>)>>>,
 }
 
 impl FakeAudioController {
     fn new(state: Arc<Mutex<FakeAudioControllerState>>) -> Self {
-        Self { state }
+        Self {
+            state,
+            external_change_listener: Arc::new(Mutex::new(None)),
+            session_event_listeners: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Simulate the user changing mute/volume themselves, as if an
+    /// `IAudioEndpointVolumeCallback` fired with a foreign `eventContext`.
+    fn trigger_external_change(&self) {
+        if let Some(listener) = self.external_change_listener.lock().unwrap().as_ref() {
+            listener();
+        }
+    }
+
+    /// Simulate an `IAudioSessionEvents` callback firing for the session
+    /// belonging to `pid`, as if it had been registered via
+    /// `RegisterAudioSessionNotification`.
+    fn trigger_session_event(&self, pid: u32, event: SessionReconciliationEvent) {
+        for (listener_pid, listener) in self.session_event_listeners.lock().unwrap().iter() {
+            if *listener_pid == pid {
+                listener(event);
+            }
+        }
     }
 }
 
@@ -44,6 +98,60 @@ impl SystemAudioControl for FakeAudioController {
         state.is_muted = muted;
         Ok(())
     }
+
+    fn get_volume(&self) -> Result<f32, AudioControlError> {
+        Ok(self.state.lock().unwrap().volume)
+    }
+
+    fn set_volume(&self, level: f32) -> Result<(), AudioControlError> {
+        let mut state = self.state.lock().unwrap();
+        state.set_volume_calls.push(level);
+        state.volume = level;
+        Ok(())
+    }
+
+    fn is_muted_for_process(&self, pid: u32) -> Result<bool, AudioControlError> {
+        let mut state = self.state.lock().unwrap();
+        state.is_muted_for_process_calls.push(pid);
+        Ok(state.is_muted)
+    }
+
+    fn set_muted_for_process(&self, pid: u32, muted: bool) -> Result<(), AudioControlError> {
+        let mut state = self.state.lock().unwrap();
+        state.set_muted_for_process_calls.push((pid, muted));
+        state.is_muted = muted;
+        Ok(())
+    }
+
+    fn is_muted_for_scope(&self, _scope: MuteScope) -> Result<bool, AudioControlError> {
+        Ok(self.state.lock().unwrap().is_muted)
+    }
+
+    fn set_muted_for_scope(&self, scope: MuteScope, muted: bool) -> Result<(), AudioControlError> {
+        let mut state = self.state.lock().unwrap();
+        state.set_muted_for_scope_calls.push((scope, muted));
+        state.is_muted = muted;
+        Ok(())
+    }
+
+    fn enumerate_sessions(&self) -> Result<Vec<AudioSession>, AudioControlError> {
+        Ok(self.state.lock().unwrap().sessions.clone())
+    }
+
+    fn register_session_event_listener(
+        &self,
+        pid: u32,
+        on_event: Box<dyn Fn(SessionReconciliationEvent) + Send + Sync>,
+    ) {
+        self.session_event_listeners
+            .lock()
+            .unwrap()
+            .push((pid, on_event));
+    }
+
+    fn register_external_change_listener(&self, on_external_change: Box<dyn Fn() + Send + Sync>) {
+        *self.external_change_listener.lock().unwrap() = Some(on_external_change);
+    }
 }
 
 #[test]
@@ -152,6 +260,289 @@ fn mute_falls_back_to_not_muted_when_is_muted_query_fails() {
     assert_eq!(state_after_operations.set_muted_calls, vec![true]);
 }
 
+#[test]
+fn decide_duck_transition_for_not_muting_lowers_volume_and_remembers_previous_level() {
+    let transition_decision = decide_duck_transition(MuteState::NotMuting, 0.8, 0.2);
+    assert_eq!(
+        transition_decision,
+        MuteTransitionDecision {
+            next_state: MuteState::DuckedByUs {
+                previous_level: 0.8
+            },
+            action: MuteTransitionAction::SetVolume(0.2),
+        }
+    );
+}
+
+#[test]
+fn decide_unmute_transition_from_ducked_by_us_restores_previous_level() {
+    let transition_decision = decide_unmute_transition(MuteState::DuckedByUs {
+        previous_level: 0.8,
+    });
+    assert_eq!(
+        transition_decision,
+        MuteTransitionDecision {
+            next_state: MuteState::NotMuting,
+            action: MuteTransitionAction::SetVolume(0.8),
+        }
+    );
+}
+
+#[test]
+fn duck_and_unmute_restore_the_exact_previous_volume() {
+    let fake_controller_state = Arc::new(Mutex::new(FakeAudioControllerState {
+        volume: 0.75,
+        ..Default::default()
+    }));
+    let fake_controller = FakeAudioController::new(fake_controller_state.clone());
+    let audio_mute_manager = AudioMuteManager::from_controller(Box::new(fake_controller));
+
+    audio_mute_manager.duck(0.1).unwrap();
+    audio_mute_manager.unmute().unwrap();
+
+    let state_after_operations = fake_controller_state.lock().unwrap();
+    assert_eq!(state_after_operations.set_volume_calls, vec![0.1, 0.75]);
+}
+
+#[test]
+fn duck_is_idempotent_when_already_ducking() {
+    let fake_controller_state = Arc::new(Mutex::new(FakeAudioControllerState {
+        volume: 0.75,
+        ..Default::default()
+    }));
+    let fake_controller = FakeAudioController::new(fake_controller_state.clone());
+    let audio_mute_manager = AudioMuteManager::from_controller(Box::new(fake_controller));
+
+    audio_mute_manager.duck(0.1).unwrap();
+    audio_mute_manager.duck(0.1).unwrap();
+
+    let state_after_operations = fake_controller_state.lock().unwrap();
+    assert_eq!(state_after_operations.set_volume_calls, vec![0.1]);
+}
+
+#[test]
+fn external_change_while_muted_by_us_backs_off_and_unmute_becomes_no_op() {
+    let fake_controller_state = Arc::new(Mutex::new(FakeAudioControllerState::default()));
+    let fake_controller = FakeAudioController::new(fake_controller_state.clone());
+    let audio_mute_manager = AudioMuteManager::from_controller(Box::new(fake_controller.clone()));
+
+    audio_mute_manager.mute().unwrap();
+    fake_controller.trigger_external_change();
+    audio_mute_manager.unmute().unwrap();
+
+    let state_after_operations = fake_controller_state.lock().unwrap();
+    assert_eq!(state_after_operations.set_muted_calls, vec![true]);
+}
+
+#[test]
+fn decide_mute_process_transition_for_not_muting_and_not_muted_sets_muted_for_process() {
+    let transition_decision = decide_mute_process_transition(MuteState::NotMuting, 1234, false);
+    assert_eq!(
+        transition_decision,
+        MuteTransitionDecision {
+            next_state: MuteState::MutedProcessByUs { pid: 1234 },
+            action: MuteTransitionAction::SetMutedForProcess {
+                pid: 1234,
+                muted: true
+            },
+        }
+    );
+}
+
+#[test]
+fn decide_unmute_transition_from_muted_process_by_us_unmutes_that_process_and_resets_state() {
+    let transition_decision = decide_unmute_transition(MuteState::MutedProcessByUs { pid: 1234 });
+    assert_eq!(
+        transition_decision,
+        MuteTransitionDecision {
+            next_state: MuteState::NotMuting,
+            action: MuteTransitionAction::SetMutedForProcess {
+                pid: 1234,
+                muted: false
+            },
+        }
+    );
+}
+
+#[test]
+fn mute_process_and_unmute_perform_expected_set_muted_for_process_calls() {
+    let fake_controller_state = Arc::new(Mutex::new(FakeAudioControllerState::default()));
+    let fake_controller = FakeAudioController::new(fake_controller_state.clone());
+    let audio_mute_manager = AudioMuteManager::from_controller(Box::new(fake_controller));
+
+    audio_mute_manager.mute_process(1234).unwrap();
+    audio_mute_manager.unmute().unwrap();
+
+    let state_after_operations = fake_controller_state.lock().unwrap();
+    assert_eq!(
+        state_after_operations.set_muted_for_process_calls,
+        vec![(1234, true), (1234, false)]
+    );
+}
+
+#[test]
+fn decide_mute_scope_transition_for_not_muting_and_not_muted_sets_muted_for_scope() {
+    let transition_decision =
+        decide_mute_scope_transition(MuteState::NotMuting, MuteScope::CommunicationsOnly, false);
+    assert_eq!(
+        transition_decision,
+        MuteTransitionDecision {
+            next_state: MuteState::MutedByScope {
+                scope: MuteScope::CommunicationsOnly
+            },
+            action: MuteTransitionAction::SetMutedForScope {
+                scope: MuteScope::CommunicationsOnly,
+                muted: true
+            },
+        }
+    );
+}
+
+#[test]
+fn decide_unmute_transition_from_muted_by_scope_unmutes_that_scope_and_resets_state() {
+    let transition_decision = decide_unmute_transition(MuteState::MutedByScope {
+        scope: MuteScope::MediaContent,
+    });
+    assert_eq!(
+        transition_decision,
+        MuteTransitionDecision {
+            next_state: MuteState::NotMuting,
+            action: MuteTransitionAction::SetMutedForScope {
+                scope: MuteScope::MediaContent,
+                muted: false
+            },
+        }
+    );
+}
+
+#[test]
+fn mute_scope_and_unmute_perform_expected_set_muted_for_scope_calls() {
+    let fake_controller_state = Arc::new(Mutex::new(FakeAudioControllerState::default()));
+    let fake_controller = FakeAudioController::new(fake_controller_state.clone());
+    let audio_mute_manager = AudioMuteManager::from_controller(Box::new(fake_controller));
+
+    audio_mute_manager
+        .mute_scope(MuteScope::CommunicationsOnly)
+        .unwrap();
+    audio_mute_manager.unmute().unwrap();
+
+    let state_after_operations = fake_controller_state.lock().unwrap();
+    assert_eq!(
+        state_after_operations.set_muted_for_scope_calls,
+        vec![
+            (MuteScope::CommunicationsOnly, true),
+            (MuteScope::CommunicationsOnly, false)
+        ]
+    );
+}
+
+#[test]
+fn session_mute_changed_externally_backs_off_and_unmute_becomes_no_op() {
+    let fake_controller_state = Arc::new(Mutex::new(FakeAudioControllerState::default()));
+    let fake_controller = FakeAudioController::new(fake_controller_state.clone());
+    let audio_mute_manager = AudioMuteManager::from_controller(Box::new(fake_controller.clone()));
+
+    audio_mute_manager.mute_process(1234).unwrap();
+    fake_controller.trigger_session_event(1234, SessionReconciliationEvent::MuteChanged(false));
+    audio_mute_manager.unmute().unwrap();
+
+    let state_after_operations = fake_controller_state.lock().unwrap();
+    assert_eq!(
+        state_after_operations.set_muted_for_process_calls,
+        vec![(1234, true)]
+    );
+}
+
+#[test]
+fn session_disconnected_resets_mute_state_so_unmute_is_a_no_op() {
+    let fake_controller_state = Arc::new(Mutex::new(FakeAudioControllerState::default()));
+    let fake_controller = FakeAudioController::new(fake_controller_state.clone());
+    let audio_mute_manager = AudioMuteManager::from_controller(Box::new(fake_controller.clone()));
+
+    audio_mute_manager.mute_process(1234).unwrap();
+    fake_controller.trigger_session_event(1234, SessionReconciliationEvent::Disconnected);
+    audio_mute_manager.unmute().unwrap();
+
+    let state_after_operations = fake_controller_state.lock().unwrap();
+    assert_eq!(
+        state_after_operations.set_muted_for_process_calls,
+        vec![(1234, true)]
+    );
+}
+
+#[test]
+fn decide_mute_all_except_process_transition_for_not_muting_mutes_all_except_process() {
+    let transition_decision = decide_mute_all_except_process_transition(MuteState::NotMuting, 1234);
+    assert_eq!(
+        transition_decision,
+        MuteTransitionDecision {
+            next_state: MuteState::MutedAllExceptProcess { except_pid: 1234 },
+            action: MuteTransitionAction::SetMutedForAllExceptProcess {
+                except_pid: 1234,
+                muted: true
+            },
+        }
+    );
+}
+
+#[test]
+fn decide_mute_all_except_process_transition_is_no_op_when_already_muting() {
+    let transition_decision = decide_mute_all_except_process_transition(MuteState::MutedByUs, 1234);
+    assert_eq!(
+        transition_decision,
+        MuteTransitionDecision {
+            next_state: MuteState::MutedByUs,
+            action: MuteTransitionAction::NoOp,
+        }
+    );
+}
+
+#[test]
+fn decide_unmute_transition_from_muted_all_except_process_unmutes_the_rest_and_resets_state() {
+    let transition_decision =
+        decide_unmute_transition(MuteState::MutedAllExceptProcess { except_pid: 1234 });
+    assert_eq!(
+        transition_decision,
+        MuteTransitionDecision {
+            next_state: MuteState::NotMuting,
+            action: MuteTransitionAction::SetMutedForAllExceptProcess {
+                except_pid: 1234,
+                muted: false
+            },
+        }
+    );
+}
+
+#[test]
+fn mute_all_except_process_and_unmute_perform_expected_set_muted_for_process_calls() {
+    let fake_controller_state = Arc::new(Mutex::new(FakeAudioControllerState {
+        sessions: vec![
+            AudioSession {
+                pid: 1234,
+                name: "Focused App".to_string(),
+                muted: false,
+            },
+            AudioSession {
+                pid: 5678,
+                name: "Other App".to_string(),
+                muted: false,
+            },
+        ],
+        ..Default::default()
+    }));
+    let fake_controller = FakeAudioController::new(fake_controller_state.clone());
+    let audio_mute_manager = AudioMuteManager::from_controller(Box::new(fake_controller));
+
+    audio_mute_manager.mute_all_except_process(1234).unwrap();
+    audio_mute_manager.unmute().unwrap();
+
+    let state_after_operations = fake_controller_state.lock().unwrap();
+    assert_eq!(
+        state_after_operations.set_muted_for_process_calls,
+        vec![(5678, true), (5678, false)]
+    );
+}
+
 #[test]
 fn drop_unmutes_when_manager_muted_audio() {
     let fake_controller_state = Arc::new(Mutex::new(FakeAudioControllerState::default()));
@@ -0,0 +1,252 @@
+use super::*;
+
+// Tests for the versioned export-file migration chain.
+
+#[test]
+fn migrate_history_v1_to_v2_renames_entry_count_to_count() {
+    let v1_document = serde_json::json!({
+        "type": HISTORY_EXPORT_TYPE,
+        "version": 1,
+        "exported_at": "2024-01-01T00:00:00Z",
+        "entry_count": 2,
+        "data": [],
+    });
+
+    let migrated = migrate_history_v1_to_v2(v1_document).expect("migration should succeed");
+
+    assert_eq!(migrated.get("entry_count"), None);
+    assert_eq!(migrated.get("count"), Some(&serde_json::json!(2)));
+}
+
+#[test]
+fn migrate_history_v1_to_v2_is_idempotent() {
+    let already_v2 = serde_json::json!({
+        "type": HISTORY_EXPORT_TYPE,
+        "version": 2,
+        "exported_at": "2024-01-01T00:00:00Z",
+        "count": 3,
+        "data": [],
+    });
+
+    let migrated = migrate_history_v1_to_v2(already_v2.clone()).expect("migration should succeed");
+
+    assert_eq!(migrated, already_v2);
+}
+
+#[test]
+fn apply_migrations_preserves_unknown_fields() {
+    let v1_document = serde_json::json!({
+        "type": HISTORY_EXPORT_TYPE,
+        "version": 1,
+        "exported_at": "2024-01-01T00:00:00Z",
+        "entry_count": 1,
+        "data": [],
+        "from_future_client": "keep me",
+    });
+
+    let migrated = apply_migrations(v1_document, 1, HISTORY_MIGRATIONS).expect("should migrate");
+
+    assert_eq!(
+        migrated.get("from_future_client"),
+        Some(&serde_json::json!("keep me"))
+    );
+    assert_eq!(
+        migrated.get("version"),
+        Some(&serde_json::json!(EXPORT_VERSION))
+    );
+}
+
+#[test]
+fn apply_migrations_on_already_current_version_is_a_noop_besides_version_stamp() {
+    let current = serde_json::json!({
+        "type": HISTORY_EXPORT_TYPE,
+        "version": EXPORT_VERSION,
+        "exported_at": "2024-01-01T00:00:00Z",
+        "count": 5,
+        "data": [],
+    });
+
+    let migrated = apply_migrations(current.clone(), EXPORT_VERSION, HISTORY_MIGRATIONS)
+        .expect("should migrate");
+
+    assert_eq!(migrated, current);
+}
+
+/// A v1 history export fixture, as it would have been produced before the
+/// `entry_count` -> `count` rename.
+const V1_HISTORY_FIXTURE: &str = r#"{
+    "type": "tambourine-history",
+    "version": 1,
+    "exported_at": "2024-01-01T00:00:00Z",
+    "entry_count": 1,
+    "data": [
+        {
+            "id": "11111111-1111-1111-1111-111111111111",
+            "timestamp": "2024-01-01T00:00:00Z",
+            "text": "hello world",
+            "raw_text": "hello world"
+        }
+    ]
+}"#;
+
+#[test]
+fn v1_history_fixture_migrates_and_deserializes_at_current_version() {
+    let document: serde_json::Value =
+        serde_json::from_str(V1_HISTORY_FIXTURE).expect("fixture should parse as JSON");
+    let from_version = document
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .expect("fixture has a version") as u32;
+
+    let migrated =
+        apply_migrations(document, from_version, HISTORY_MIGRATIONS).expect("should migrate");
+    let export: HistoryExportFile =
+        serde_json::from_value(migrated).expect("migrated document should deserialize");
+
+    assert_eq!(export.version, EXPORT_VERSION);
+    assert_eq!(export.count, 1);
+    assert_eq!(export.data.len(), 1);
+    assert_eq!(export.data[0].text, "hello world");
+}
+
+/// A v1 settings export fixture, missing every field that the current
+/// `SettingsExportData` would write - `#[serde(default)]` should fill them
+/// in rather than the import failing outright.
+const V1_SETTINGS_FIXTURE: &str = r#"{
+    "type": "tambourine-settings",
+    "version": 1,
+    "exported_at": "2024-01-01T00:00:00Z",
+    "data": {}
+}"#;
+
+#[test]
+fn v1_settings_fixture_migrates_and_deserializes_at_current_version() {
+    let document: serde_json::Value =
+        serde_json::from_str(V1_SETTINGS_FIXTURE).expect("fixture should parse as JSON");
+    let from_version = document
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .expect("fixture has a version") as u32;
+
+    let migrated =
+        apply_migrations(document, from_version, SETTINGS_MIGRATIONS).expect("should migrate");
+    let export: SettingsExportFile =
+        serde_json::from_value(migrated).expect("migrated document should deserialize");
+
+    assert_eq!(export.version, EXPORT_VERSION);
+    assert_eq!(
+        export.data.sound_enabled,
+        AppSettings::default().sound_enabled
+    );
+}
+
+#[test]
+fn parse_lenient_json_tolerates_comments_and_trailing_commas() {
+    let hand_edited = r#"{
+        // exported from an older build
+        "type": "tambourine-settings",
+        "version": 1,
+        "exported_at": "2024-01-01T00:00:00Z",
+        "data": {
+            "sound_enabled": true, /* left on after testing */
+        },
+    }"#;
+
+    let document = parse_lenient_json(hand_edited, "settings").expect("should parse leniently");
+
+    assert_eq!(
+        document.get("type").and_then(serde_json::Value::as_str),
+        Some(SETTINGS_EXPORT_TYPE)
+    );
+}
+
+#[test]
+fn parse_lenient_json_still_rejects_genuine_garbage() {
+    assert!(parse_lenient_json("not json at all", "settings").is_err());
+}
+
+#[test]
+fn detect_export_file_type_rejects_a_version_newer_than_supported() {
+    let future_version_document = serde_json::json!({
+        "type": HISTORY_EXPORT_TYPE,
+        "version": EXPORT_VERSION + 1,
+    })
+    .to_string();
+
+    assert!(matches!(
+        detect_export_file_type(future_version_document),
+        DetectedFileType::Unknown
+    ));
+}
+
+#[test]
+fn detect_export_file_type_recognizes_a_prompt_bundle() {
+    let document = serde_json::json!({
+        "type": PROMPT_BUNDLE_EXPORT_TYPE,
+        "version": EXPORT_VERSION,
+        "exported_at": "2024-01-01T00:00:00Z",
+        "profiles": {},
+    })
+    .to_string();
+
+    assert!(matches!(
+        detect_export_file_type(document),
+        DetectedFileType::PromptBundle
+    ));
+}
+
+#[test]
+fn settings_export_round_trips_through_yaml() {
+    let export = SettingsExportFile {
+        file_type: SETTINGS_EXPORT_TYPE.to_string(),
+        version: EXPORT_VERSION,
+        exported_at: Utc::now(),
+        data: AppSettings::default().into(),
+    };
+
+    let yaml = serialize_export_document(&export, ExportFileFormat::Yaml, "settings")
+        .expect("should serialize to yaml");
+    assert!(!yaml.trim_start().starts_with('{'), "{yaml}");
+
+    let document = parse_export_document(&yaml, "settings").expect("should parse as yaml");
+    let reparsed: SettingsExportFile =
+        serde_json::from_value(document).expect("should deserialize");
+
+    assert_eq!(reparsed.version, EXPORT_VERSION);
+    assert_eq!(
+        reparsed.data.sound_enabled,
+        AppSettings::default().sound_enabled
+    );
+}
+
+#[test]
+fn parse_export_document_reports_both_failures_on_genuine_garbage() {
+    let error = parse_export_document("not json or yaml: [", "settings")
+        .expect_err("should fail to parse as either format");
+
+    assert!(error.contains("JSON"));
+    assert!(error.contains("YAML"));
+}
+
+#[test]
+fn prompt_bundle_round_trips_through_json() {
+    let mut profiles = std::collections::HashMap::new();
+    profiles.insert("email".to_string(), CleanupPromptSections::default());
+
+    let export = PromptBundleExportFile {
+        file_type: PROMPT_BUNDLE_EXPORT_TYPE.to_string(),
+        version: EXPORT_VERSION,
+        exported_at: Utc::now(),
+        profiles,
+    };
+
+    let serialized = serde_json::to_string(&export).expect("should serialize");
+    let deserialized: PromptBundleExportFile =
+        serde_json::from_str(&serialized).expect("should deserialize");
+
+    assert_eq!(deserialized.file_type, PROMPT_BUNDLE_EXPORT_TYPE);
+    assert_eq!(
+        deserialized.profiles.get("email"),
+        Some(&CleanupPromptSections::default())
+    );
+}
@@ -27,6 +27,7 @@ fn build_active_app_context_snapshot_for_test(
             origin: browser_tab_origin.map(str::to_string),
             browser: None,
         }),
+        focused_text_element: None,
         event_source: FocusEventSource::Polling,
         confidence_level,
         captured_at: captured_at.to_string(),